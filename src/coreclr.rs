@@ -0,0 +1,564 @@
+//! The object model of CoreCLR, which Everest runs Celeste on instead of mono.
+//!
+//! CoreCLR keeps type names in the assembly's ECMA-335 metadata rather than in its runtime
+//! structures, so types and fields are found by token: the metadata of the mapped Celeste.dll and
+//! core libraries gives the tokens, and the runtime's method tables and field descs are matched
+//! against them.
+//! Static fields are read from the `DomainLocalModule` of the assembly's `Module`.
+//! The runtime structure offsets below are those of .NET 7 on x86_64.
+
+use {
+    crate::{
+        error::AttachError,
+        managed::{self, Layout},
+        object_model::{ObjectModel, ProcessCaches},
+    },
+    asr::{MemoryRangeFlags, Process, ProcessId},
+    bytemuck::Pod,
+    std::{
+        cell::{Cell, RefCell},
+        collections::BTreeMap,
+        ops::Range,
+    },
+};
+
+const MT_TOKEN: u64 = 0x0a;
+const MT_PARENT: u64 = 0x10;
+const MT_LOADER_MODULE: u64 = 0x18;
+const MT_EECLASS: u64 = 0x28;
+const EECLASS_METHOD_TABLE: u64 = 0x10;
+const EECLASS_FIELD_DESCS: u64 = 0x18;
+const FIELD_DESC_SIZE: u64 = 0x10;
+/// Instance fields are laid out after the method table pointer.
+const OBJECT_FIELDS: u64 = 0x8;
+
+const SCAN_CHUNK: usize = 0x10_0000;
+
+fn read_at<T: Pod>(data: &[u8], offset: usize) -> Option<T> {
+    let bytes = data.get(offset..offset.checked_add(std::mem::size_of::<T>())?)?;
+    Some(bytemuck::pod_read_unaligned(bytes))
+}
+
+fn read_cstr(data: &[u8], offset: usize) -> Option<&str> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0)?;
+    std::str::from_utf8(&bytes[..end]).ok()
+}
+
+struct TypeDef {
+    name: String,
+    /// 1-based rows of the Field table declared by this type
+    fields: Range<u32>,
+}
+
+struct Field {
+    name: String,
+    flags: u16,
+}
+
+const FIELD_LITERAL: u16 = 0x40;
+
+/// The TypeDef and Field tables of an assembly's metadata.
+struct Metadata {
+    type_defs: Vec<TypeDef>,
+    fields: Vec<Field>,
+}
+
+impl Metadata {
+    fn parse(image: &[u8]) -> Option<Metadata> {
+        Metadata::parse_layout(image, false).or_else(|| Metadata::parse_layout(image, true))
+    }
+
+    fn parse_layout(image: &[u8], flat: bool) -> Option<Metadata> {
+        let rva = |rva: u32| rva_to_offset(image, rva, flat);
+
+        let pe = read_at::<u32>(image, 0x3c)? as usize;
+        if read_at::<u32>(image, pe)? != 0x0000_4550 {
+            return None;
+        }
+        let optional = pe + 24;
+        let directories = match read_at::<u16>(image, optional)? {
+            0x10b => optional + 96,
+            0x20b => optional + 112,
+            _ => return None,
+        };
+        let cli_header = rva(read_at::<u32>(image, directories + 14 * 8)?)?;
+        let root = rva(read_at::<u32>(image, cli_header + 8)?)?;
+        if read_at::<u32>(image, root)? != 0x424a_5342 {
+            return None;
+        }
+
+        let version_len = read_at::<u32>(image, root + 12)? as usize;
+        let num_streams = read_at::<u16>(image, root + 16 + version_len + 2)?;
+        let mut header = root + 16 + version_len + 4;
+        let mut tables = None;
+        let mut strings = None;
+        for _ in 0..num_streams {
+            let offset = read_at::<u32>(image, header)? as usize;
+            let name = read_cstr(image, header + 8)?;
+            match name {
+                "#~" => tables = Some(root + offset),
+                "#Strings" => strings = Some(root + offset),
+                _ => {}
+            }
+            header += 8 + (name.len() + 4) / 4 * 4;
+        }
+        let (tables, strings) = (tables?, strings?);
+
+        let heap_sizes = read_at::<u8>(image, tables + 6)?;
+        let valid = read_at::<u64>(image, tables + 8)?;
+        let mut rows = [0u32; 64];
+        let mut cursor = tables + 24;
+        for (table, count) in rows.iter_mut().enumerate() {
+            if valid & (1 << table) != 0 {
+                *count = read_at::<u32>(image, cursor)?;
+                cursor += 4;
+            }
+        }
+
+        let string_index = if heap_sizes & 0x1 != 0 { 4 } else { 2 };
+        let guid_index = if heap_sizes & 0x2 != 0 { 4 } else { 2 };
+        let blob_index = if heap_sizes & 0x4 != 0 { 4 } else { 2 };
+        let table_index = |table: usize| if rows[table] < 0x1_0000 { 2 } else { 4 };
+        let coded_index = |tables: &[usize], tag_bits: u32| {
+            let max = tables.iter().map(|&table| rows[table]).max().unwrap_or(0);
+            if max < 1 << (16 - tag_bits) { 2 } else { 4 }
+        };
+        let read_index = |offset: usize, size: usize| -> Option<u32> {
+            if size == 2 { read_at::<u16>(image, offset).map(u32::from) } else { read_at::<u32>(image, offset) }
+        };
+        let read_string = |index: u32| read_cstr(image, strings + index as usize).map(str::to_owned);
+
+        // the tables before TypeDef and Field, in table order
+        let module_row = 2 + string_index + 3 * guid_index;
+        let type_ref_row = coded_index(&[0x00, 0x1a, 0x23, 0x01], 2) + 2 * string_index;
+        let extends = coded_index(&[0x02, 0x01, 0x1b], 2);
+        let type_def_row = 4 + 2 * string_index + extends + table_index(0x04) + table_index(0x06);
+        let field_ptr_row = table_index(0x04);
+        let field_row = 2 + string_index + blob_index;
+
+        let type_def_start = cursor + rows[0x00] as usize * module_row + rows[0x01] as usize * type_ref_row;
+        let field_start = type_def_start + rows[0x02] as usize * type_def_row + rows[0x03] as usize * field_ptr_row;
+
+        let mut field_lists = Vec::with_capacity(rows[0x02] as usize);
+        let mut names = Vec::with_capacity(rows[0x02] as usize);
+        for i in 0..rows[0x02] as usize {
+            let row = type_def_start + i * type_def_row;
+            names.push(read_string(read_index(row + 4, string_index)?)?);
+            field_lists.push(read_index(row + 4 + 2 * string_index + extends, table_index(0x04))?);
+        }
+        let type_defs = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| TypeDef {
+                name,
+                fields: field_lists[i]..field_lists.get(i + 1).copied().unwrap_or(rows[0x04] + 1),
+            })
+            .collect();
+
+        let mut fields = Vec::with_capacity(rows[0x04] as usize);
+        for i in 0..rows[0x04] as usize {
+            let row = field_start + i * field_row;
+            fields.push(Field {
+                flags: read_at::<u16>(image, row)?,
+                name: read_string(read_index(row + 2, string_index)?)?,
+            });
+        }
+
+        Some(Metadata { type_defs, fields })
+    }
+
+    fn field(&self, row: u32) -> Option<&Field> {
+        self.fields.get(row.checked_sub(1)? as usize)
+    }
+}
+
+/// Converts an RVA to an offset into the image, which is either mapped by section (`flat` is
+/// false) or as a flat copy of the file.
+fn rva_to_offset(image: &[u8], rva: u32, flat: bool) -> Option<usize> {
+    if !flat {
+        return Some(rva as usize);
+    }
+    let pe = read_at::<u32>(image, 0x3c)? as usize;
+    let num_sections = read_at::<u16>(image, pe + 6)? as usize;
+    let optional_size = read_at::<u16>(image, pe + 20)? as usize;
+    let sections = pe + 24 + optional_size;
+    for i in 0..num_sections {
+        let section = sections + i * 40;
+        let virtual_size = read_at::<u32>(image, section + 8)?;
+        let virtual_address = read_at::<u32>(image, section + 12)?;
+        let raw_pointer = read_at::<u32>(image, section + 20)?;
+        if rva >= virtual_address && rva - virtual_address < virtual_size {
+            return Some((rva - virtual_address + raw_pointer) as usize);
+        }
+    }
+    None
+}
+
+/// Calls `f` with every 8-byte aligned value in the target's writable memory and its address.
+fn scan_writable(process: &Process, mut f: impl FnMut(u64, u64) -> bool) -> Option<u64> {
+    let mut buf = vec![0u64; SCAN_CHUNK / 8];
+    for range in process.memory_ranges() {
+        if !range.flags().is_ok_and(|flags| flags.contains(MemoryRangeFlags::WRITE)) {
+            continue;
+        }
+        let (start, size) = match range.range() {
+            Ok((start, size)) => (start.value(), size),
+            Err(_) => continue,
+        };
+        let mut address = start;
+        while address < start + size {
+            let len = ((start + size - address) as usize).min(SCAN_CHUNK) / 8;
+            if process.read_into_slice(address, &mut buf[..len]).is_ok() {
+                for (i, &value) in buf[..len].iter().enumerate() {
+                    let at = address + i as u64 * 8;
+                    if f(at, value) {
+                        return Some(at);
+                    }
+                }
+            }
+            address += SCAN_CHUNK as u64;
+        }
+    }
+    None
+}
+
+/// The method table that a generic instantiation shares its EEClass and field descs with, which
+/// is the type itself for anything else. Instantiations over value types aren't shared, and so
+/// are canonical themselves.
+fn canonical_method_table(process: &Process, mt: u64) -> Option<u64> {
+    let eeclass_or_canonical = process.read::<u64>(mt + MT_EECLASS).ok()?;
+    Some(if eeclass_or_canonical & 1 != 0 { eeclass_or_canonical & !1 } else { mt })
+}
+
+fn is_method_table(process: &Process, mt: u64) -> bool {
+    // a method table is pointed at by its own EEClass, which no lookalike data will be
+    process
+        .read::<u64>(mt + MT_EECLASS)
+        .ok()
+        .filter(|eeclass| eeclass & 1 == 0)
+        .and_then(|eeclass| process.read::<u64>(eeclass + EECLASS_METHOD_TABLE).ok())
+        == Some(mt)
+}
+
+/// Finds the method table of the type in the given TypeDef row of the assembly that `is_module`
+/// accepts the loader module of. Every assembly has types in the same rows, so the row alone
+/// doesn't identify one.
+fn find_method_table(process: &Process, row: u32, mut is_module: impl FnMut(u64) -> bool) -> Option<u64> {
+    // the token sits in the third and fourth bytes of the method table's second qword
+    let token_word = MT_TOKEN & !7;
+    let token_shift = (MT_TOKEN & 7) * 8;
+    let found = scan_writable(process, |at, value| {
+        (value >> token_shift) as u16 == row as u16
+            && at >= token_word
+            && is_method_table(process, at - token_word)
+            && process.read::<u64>(at - token_word + MT_LOADER_MODULE).is_ok_and(&mut is_module)
+    })?;
+    Some(found - token_word)
+}
+
+/// How far into a `Module` to look for a pointer into its mapped image, such as its name.
+const MODULE_SCAN_QWORDS: usize = 64;
+
+const ELEMENT_TYPE_STRING: u8 = 0x0e;
+const ELEMENT_TYPE_CLASS: u8 = 0x12;
+
+/// How many calls skip looking for a method table or the module's statics after a search found
+/// nothing. Types are only loaded once the game first uses them, the statics can only be told apart
+/// once `Celeste.Instance` is set, and searching every tick would stall the timer.
+const FAILED_SCAN_COOLDOWN: u32 = 600;
+
+/// How far into a `Module` to look for its `DomainLocalModule`.
+const MODULE_STATICS_SCAN_QWORDS: usize = 0x100;
+/// `DomainLocalModule::m_pGCStatics`, the slots of the module's reference-typed statics, which the
+/// field desc offsets of those statics are from.
+const DLM_GC_STATICS: u64 = 0x18;
+
+struct FieldDesc {
+    rid: u32,
+    is_static: bool,
+    /// The CorElementType of the field, which only tells references apart from values.
+    element_type: u8,
+    offset: u64,
+}
+
+/// Everything a `CoreClr` had to scan memory for, which is too slow to redo on every attach.
+#[derive(Default)]
+struct Scanned {
+    assemblies: Vec<Assembly>,
+    modules: BTreeMap<&'static str, u64>,
+    method_tables: BTreeMap<(&'static str, u32), u64>,
+    failed_scans: BTreeMap<(&'static str, u32), u32>,
+    gc_statics: Option<u64>,
+    statics_skips: u32,
+}
+
+static SCANNED: ProcessCaches<Scanned> = ProcessCaches::new();
+
+pub fn forget_processes(pids: &[ProcessId]) {
+    SCANNED.forget_processes(pids);
+}
+
+/// The assemblies that classes are looked up in after the game's own: the ones defining the
+/// collections and other built-in types that the game keeps its state in.
+const LIBRARY_ASSEMBLIES: [&str; 2] = ["System.Private.CoreLib.dll", "System.Collections.dll"];
+
+/// An assembly mapped into the process, with the metadata its types are looked up in.
+struct Assembly {
+    name: &'static str,
+    metadata: Metadata,
+    /// Where the assembly is mapped, which its `Module` has pointers into.
+    image: Range<u64>,
+}
+
+impl Assembly {
+    fn load(process: &Process, name: &'static str) -> Option<Assembly> {
+        let (image_base, image_size) = process.get_module_range(name).ok()?;
+        let mut image = vec![0u8; image_size as usize];
+        for (i, page) in image.chunks_mut(0x1000).enumerate() {
+            let _ = process.read_into_slice(image_base.value() + i as u64 * 0x1000, page);
+        }
+        Some(Assembly {
+            name,
+            metadata: Metadata::parse(&image)?,
+            image: image_base.value()..image_base.value() + image_size,
+        })
+    }
+}
+
+/// The CoreCLR runtime, with the metadata of the Celeste assembly and the core libraries.
+pub struct CoreClr {
+    pid: ProcessId,
+    /// Celeste's assembly, followed by whichever of `LIBRARY_ASSEMBLIES` are loaded.
+    assemblies: Vec<Assembly>,
+    /// The loader module of each assembly's types, by its name, once one has been found.
+    modules: RefCell<BTreeMap<&'static str, u64>>,
+    /// Method tables by assembly and TypeDef row, since finding one scans all writable memory.
+    method_tables: RefCell<BTreeMap<(&'static str, u32), u64>>,
+    /// Types whose last method table scan failed, and how many more calls to skip scanning for.
+    failed_scans: RefCell<BTreeMap<(&'static str, u32), u32>>,
+    /// The GC statics of Celeste's module, once they have been found.
+    gc_statics: Cell<Option<u64>>,
+    /// How many more calls to skip looking for `gc_statics` for, after a search found nothing.
+    statics_skips: Cell<u32>,
+}
+
+impl CoreClr {
+    /// Reuses the assemblies parsed by an earlier attach to the process, and only loads the ones
+    /// that weren't mapped yet.
+    pub fn attach(process: &Process, pid: ProcessId) -> Result<CoreClr, AttachError> {
+        let mut scanned = SCANNED.take(pid);
+        let mut assemblies = Vec::new();
+        for name in std::iter::once("Celeste.dll").chain(LIBRARY_ASSEMBLIES) {
+            let assembly = match scanned.assemblies.iter().position(|assembly| assembly.name == name) {
+                Some(index) => Some(scanned.assemblies.swap_remove(index)),
+                None => Assembly::load(process, name),
+            };
+            match assembly {
+                Some(assembly) => assemblies.push(assembly),
+                None if assemblies.is_empty() => {
+                    SCANNED.keep(pid, scanned);
+                    return Err(AttachError::AssemblyMissing);
+                }
+                None => {}
+            }
+        }
+        Ok(CoreClr {
+            pid,
+            assemblies,
+            modules: RefCell::new(scanned.modules),
+            method_tables: RefCell::new(scanned.method_tables),
+            failed_scans: RefCell::new(scanned.failed_scans),
+            gc_statics: Cell::new(scanned.gc_statics),
+            statics_skips: Cell::new(scanned.statics_skips),
+        })
+    }
+
+    /// Whether a loader module is the given assembly's, which is told by it pointing into the
+    /// assembly's image.
+    fn is_module(&self, process: &Process, assembly: &Assembly, module: u64) -> bool {
+        if let Some(&known) = self.modules.borrow().get(assembly.name) {
+            return module == known;
+        }
+        let mut qwords = [0u64; MODULE_SCAN_QWORDS];
+        if module == 0 || process.read_into_slice(module, &mut qwords).is_err() || !qwords.iter().any(|ptr| assembly.image.contains(ptr)) {
+            return false;
+        }
+        self.modules.borrow_mut().insert(assembly.name, module);
+        true
+    }
+
+    fn method_table(&self, process: &Process, assembly: &Assembly, row: u32) -> Option<u64> {
+        let key = (assembly.name, row);
+        if let Some(&method_table) = self.method_tables.borrow().get(&key) {
+            return Some(method_table);
+        }
+        if let Some(skips) = self.failed_scans.borrow_mut().get_mut(&key) {
+            if *skips > 0 {
+                *skips -= 1;
+                return None;
+            }
+        }
+        let Some(method_table) = find_method_table(process, row, |module| self.is_module(process, assembly, module)) else {
+            self.failed_scans.borrow_mut().insert(key, FAILED_SCAN_COOLDOWN);
+            return None;
+        };
+        self.failed_scans.borrow_mut().remove(&key);
+        self.method_tables.borrow_mut().insert(key, method_table);
+        Some(method_table)
+    }
+
+    /// The assembly and TypeDef row of a method table, and its canonical method table. Only types
+    /// loaded by the module of one of `assemblies` are found, so generic instantiations over
+    /// value types of other assemblies aren't.
+    fn type_def(&self, process: &Process, method_table: u64) -> Option<(&Assembly, u32, u64)> {
+        let canonical = canonical_method_table(process, method_table)?;
+        let module = process.read::<u64>(canonical + MT_LOADER_MODULE).ok()?;
+        let assembly = self.assemblies.iter().find(|assembly| self.is_module(process, assembly, module))?;
+        let row = process.read::<u16>(canonical + MT_TOKEN).ok()? as u32;
+        assembly.metadata.type_defs.get(row.checked_sub(1)? as usize)?;
+        Some((assembly, row, canonical))
+    }
+
+    fn field_descs(&self, process: &Process, method_table: u64) -> Option<(&Assembly, Vec<FieldDesc>)> {
+        let (assembly, row, method_table) = self.type_def(process, method_table)?;
+        let metadata = &assembly.metadata;
+        let field_rows = metadata.type_defs[row as usize - 1].fields.clone();
+        let num_field_descs = field_rows
+            .clone()
+            .filter(|&field| metadata.field(field).is_some_and(|f| f.flags & FIELD_LITERAL == 0))
+            .count() as u64;
+
+        let eeclass = process.read::<u64>(method_table + MT_EECLASS).ok()?;
+        let field_descs = process.read::<u64>(eeclass + EECLASS_FIELD_DESCS).ok()?;
+        let mut descs = Vec::new();
+        for i in 0..num_field_descs {
+            let desc = field_descs + i * FIELD_DESC_SIZE;
+            if process.read::<u64>(desc).ok()? != method_table {
+                break;
+            }
+            let token_and_flags = process.read::<u32>(desc + 8).ok()?;
+            let rid = token_and_flags & 0x00ff_ffff;
+            if !field_rows.contains(&rid) {
+                continue;
+            }
+            let type_and_offset = process.read::<u32>(desc + 12).ok()?;
+            descs.push(FieldDesc {
+                rid,
+                is_static: token_and_flags & (1 << 24) != 0,
+                element_type: (type_and_offset >> 27) as u8,
+                offset: (type_and_offset & 0x07ff_ffff) as u64,
+            });
+        }
+        Some((assembly, descs))
+    }
+
+    /// Finds a field declared by this class or its ancestors, for as long as they belong to one
+    /// of `assemblies`.
+    fn find_field(&self, process: &Process, class: u64, name: &str) -> Option<FieldDesc> {
+        let mut class = class;
+        loop {
+            let (assembly, descs) = self.field_descs(process, class)?;
+            let found = descs.into_iter().find(|desc| assembly.metadata.field(desc.rid).is_some_and(|f| f.name == name));
+            if found.is_some() {
+                return found;
+            }
+            class = process.read::<u64>(class + MT_PARENT).ok().filter(|&parent| parent != 0)?;
+        }
+    }
+
+    /// The GC statics of Celeste's module. The `DomainLocalModule` that holds them is among the
+    /// first pointers of the `Module`, and is told apart by `Celeste.Instance` being in them.
+    fn gc_statics(&self, process: &Process) -> Option<u64> {
+        if let Some(gc_statics) = self.gc_statics.get() {
+            return Some(gc_statics);
+        }
+        let skips = self.statics_skips.get();
+        if skips > 0 {
+            self.statics_skips.set(skips - 1);
+            return None;
+        }
+        let found = self.find_gc_statics(process);
+        self.gc_statics.set(found);
+        if found.is_none() {
+            self.statics_skips.set(FAILED_SCAN_COOLDOWN);
+        }
+        found
+    }
+
+    fn find_gc_statics(&self, process: &Process) -> Option<u64> {
+        let celeste = self.lookup_class(process, "Celeste").ok()?;
+        let instance = self.find_field(process, celeste, "Instance").filter(|desc| desc.is_static)?;
+        let mut qwords = [0u64; MODULE_STATICS_SCAN_QWORDS];
+        let module = *self.modules.borrow().get(self.assemblies[0].name)?;
+        process.read_into_slice(module, &mut qwords).ok()?;
+        qwords.iter().filter(|&&ptr| ptr != 0 && ptr & 7 == 0 && ptr >> 48 == 0).find_map(|&domain_local_module| {
+            let gc_statics = process.read::<u64>(domain_local_module + DLM_GC_STATICS).ok().filter(|&ptr| ptr != 0 && ptr >> 48 == 0)?;
+            let object = process.read::<u64>(gc_statics + instance.offset).ok().filter(|&ptr| ptr != 0)?;
+            (process.read::<u64>(object).ok()? == celeste).then_some(gc_statics)
+        })
+    }
+}
+
+impl Drop for CoreClr {
+    fn drop(&mut self) {
+        let scanned = Scanned {
+            assemblies: std::mem::take(&mut self.assemblies),
+            modules: self.modules.take(),
+            method_tables: self.method_tables.take(),
+            failed_scans: self.failed_scans.take(),
+            gc_statics: self.gc_statics.get(),
+            statics_skips: self.statics_skips.get(),
+        };
+        SCANNED.keep(self.pid, scanned);
+    }
+}
+
+impl ObjectModel for CoreClr {
+    fn lookup_class(&self, process: &Process, name: &str) -> Result<u64, AttachError> {
+        self.assemblies
+            .iter()
+            .find_map(|assembly| {
+                let index = assembly.metadata.type_defs.iter().position(|t| t.name == name)?;
+                self.method_table(process, assembly, index as u32 + 1)
+            })
+            .ok_or_else(|| AttachError::ClassNotFound(name.to_owned()))
+    }
+
+    /// Statics of value types live in the module's data blob rather than its GC statics, and
+    /// aren't supported.
+    fn field_offset(&self, process: &Process, class: u64, name: &str) -> Result<u64, AttachError> {
+        let desc = self
+            .find_field(process, class, name)
+            .filter(|desc| !desc.is_static || matches!(desc.element_type, ELEMENT_TYPE_CLASS | ELEMENT_TYPE_STRING))
+            .ok_or_else(|| AttachError::FieldNotFound(name.to_owned()))?;
+        Ok(if desc.is_static { desc.offset } else { desc.offset + OBJECT_FIELDS })
+    }
+
+    /// The GC statics are shared by every type of the module, so the class only has to be one of
+    /// Celeste's. Only Celeste's statics are found.
+    fn static_storage(&self, process: &Process, class: u64) -> Option<u64> {
+        let (assembly, _, _) = self.type_def(process, class)?;
+        if assembly.name != self.assemblies[0].name {
+            return None;
+        }
+        self.gc_statics(process)
+    }
+
+    fn instance_class(&self, process: &Process, instance: u64) -> Option<u64> {
+        process.read(instance).ok()
+    }
+
+    fn class_name(&self, process: &Process, class: u64) -> Option<String> {
+        let (assembly, row, _) = self.type_def(process, class)?;
+        Some(assembly.metadata.type_defs[row as usize - 1].name.clone())
+    }
+
+    fn layout(&self) -> Layout {
+        managed::CORECLR
+    }
+
+    fn runtime(&self) -> String {
+        "CoreCLR".to_owned()
+    }
+}
@@ -0,0 +1,321 @@
+//! Reading the dynamic symbol table of an ELF module loaded into the game's process.
+
+use {asr::Process, bytemuck::Pod};
+
+const ET_EXEC: u16 = 2;
+
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+
+const DT_NULL: u64 = 0;
+const DT_HASH: u64 = 4;
+const DT_STRTAB: u64 = 5;
+const DT_SYMTAB: u64 = 6;
+const DT_GNU_HASH: u64 = 0x6fff_fef5;
+
+const SYMBOL_SIZE: u64 = 24;
+const MAX_SYMBOL_NAME: usize = 128;
+
+/// Somewhere a module's image can be read from, by the addresses it is mapped at.
+pub trait Memory {
+    fn read_bytes(&self, address: u64, buffer: &mut [u8]) -> Option<()>;
+
+    fn read<T: Pod>(&self, address: u64) -> Option<T> {
+        let mut value = T::zeroed();
+        self.read_bytes(address, bytemuck::bytes_of_mut(&mut value))?;
+        Some(value)
+    }
+}
+
+impl Memory for Process {
+    fn read_bytes(&self, address: u64, buffer: &mut [u8]) -> Option<()> {
+        self.read_into_buf(address, buffer).ok()
+    }
+}
+
+/// The addresses of the program headers of the module whose file header is at `base`.
+fn program_headers<M: Memory>(memory: &M, base: u64) -> Option<impl Iterator<Item = u64>> {
+    if memory.read::<[u8; 4]>(base)? != *b"\x7fELF" {
+        return None;
+    }
+    let phoff = memory.read::<u64>(base + 0x20)?;
+    let phentsize = memory.read::<u16>(base + 0x36)? as u64;
+    let phnum = memory.read::<u16>(base + 0x38)? as u64;
+    Some((0..phnum).map(move |i| base + phoff + i * phentsize))
+}
+
+/// Compares a nul-terminated string in memory against `name`.
+fn name_matches<M: Memory>(memory: &M, address: u64, name: &str) -> bool {
+    if name.len() >= MAX_SYMBOL_NAME {
+        return false;
+    }
+    // the name and its terminator, which is all that needs to be readable
+    let mut buffer = [0u8; MAX_SYMBOL_NAME];
+    let buffer = &mut buffer[..name.len() + 1];
+    memory.read_bytes(address, buffer).is_some() && buffer[..name.len()] == *name.as_bytes() && buffer[name.len()] == 0
+}
+
+/// The tables of a module that are needed to look up its exported symbols.
+pub struct Elf {
+    /// What the module's virtual addresses are offset by, which is zero for executables that
+    /// aren't position independent.
+    bias: u64,
+    symtab: u64,
+    strtab: u64,
+    hash: Option<u64>,
+    gnu_hash: Option<u64>,
+}
+
+impl Elf {
+    /// Finds the dynamic section of the module whose file header is at `base` through its
+    /// program headers.
+    pub fn parse<M: Memory>(memory: &M, base: u64) -> Option<Elf> {
+        let headers: Vec<u64> = program_headers(memory, base)?.collect();
+        // the file header is mapped at the start of the first segment, wherever it asked to be
+        let first_load = headers.iter().find_map(|&header| {
+            (memory.read::<u32>(header)? == PT_LOAD).then_some(())?;
+            memory.read::<u64>(header + 0x10)
+        })?;
+        let bias = if memory.read::<u16>(base + 0x10)? == ET_EXEC { 0 } else { base.wrapping_sub(first_load) };
+        let dynamic = headers.iter().find_map(|&header| {
+            (memory.read::<u32>(header)? == PT_DYNAMIC).then_some(())?;
+            Some(bias.wrapping_add(memory.read::<u64>(header + 0x10)?))
+        })?;
+
+        let (mut symtab, mut strtab, mut hash, mut gnu_hash) = (None, None, None, None);
+        for entry in (dynamic..).step_by(16) {
+            let [tag, value] = memory.read::<[u64; 2]>(entry)?;
+            // the loader may or may not have relocated these in place
+            let value = if value < base { bias.wrapping_add(value) } else { value };
+            match tag {
+                DT_NULL => break,
+                DT_HASH => hash = Some(value),
+                DT_STRTAB => strtab = Some(value),
+                DT_SYMTAB => symtab = Some(value),
+                DT_GNU_HASH => gnu_hash = Some(value),
+                _ => (),
+            }
+        }
+        Some(Elf { bias, symtab: symtab?, strtab: strtab?, hash, gnu_hash })
+    }
+
+    /// The address of an exported symbol.
+    pub fn symbol<M: Memory>(&self, memory: &M, name: &str) -> Option<u64> {
+        match self.gnu_hash {
+            Some(gnu_hash) => self.gnu_lookup(memory, gnu_hash, name),
+            None => self.linear_lookup(memory, name),
+        }
+    }
+
+    fn symbol_at<M: Memory>(&self, memory: &M, index: u64, name: &str) -> Option<u64> {
+        let symbol = self.symtab + index * SYMBOL_SIZE;
+        let name_offset = memory.read::<u32>(symbol)? as u64;
+        let value = memory.read::<u64>(symbol + 8)?;
+        // undefined symbols are imports from other modules
+        if value == 0 || !name_matches(memory, self.strtab + name_offset, name) {
+            return None;
+        }
+        Some(self.bias.wrapping_add(value))
+    }
+
+    fn gnu_lookup<M: Memory>(&self, memory: &M, table: u64, name: &str) -> Option<u64> {
+        let [bucket_count, symoffset, bloom_size, bloom_shift] = memory.read::<[u32; 4]>(table)?;
+        if bucket_count == 0 || bloom_size == 0 {
+            return None;
+        }
+        let hash = name.bytes().fold(5381u32, |h, c| h.wrapping_mul(33).wrapping_add(c as u32));
+
+        let bloom = table + 16;
+        let word = memory.read::<u64>(bloom + (hash / 64 % bloom_size) as u64 * 8)?;
+        let mask = 1 << (hash % 64) | 1 << ((hash >> bloom_shift) % 64);
+        if word & mask != mask {
+            return None;
+        }
+
+        let buckets = bloom + bloom_size as u64 * 8;
+        let chains = buckets + bucket_count as u64 * 4;
+        let mut index = memory.read::<u32>(buckets + (hash % bucket_count) as u64 * 4)?;
+        if index < symoffset {
+            return None;
+        }
+        loop {
+            let chain_hash = memory.read::<u32>(chains + (index - symoffset) as u64 * 4)?;
+            if chain_hash | 1 == hash | 1 {
+                if let Some(address) = self.symbol_at(memory, index as u64, name) {
+                    return Some(address);
+                }
+            }
+            // the low bit marks the end of a bucket's chain
+            if chain_hash & 1 != 0 {
+                return None;
+            }
+            index += 1;
+        }
+    }
+
+    fn linear_lookup<M: Memory>(&self, memory: &M, name: &str) -> Option<u64> {
+        // the sysv hash table knows the symbol count; without one, .dynstr directly follows .dynsym
+        let count = match self.hash {
+            Some(hash) => memory.read::<u32>(hash + 4)? as u64,
+            None => self.strtab.checked_sub(self.symtab)? / SYMBOL_SIZE,
+        };
+        (0..count.min(0x10_0000)).find_map(|index| self.symbol_at(memory, index, name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An ELF file, read as if the loader had mapped its segments at the addresses they ask for,
+    /// offset by `bias`.
+    struct FileImage<'a> {
+        bytes: &'a [u8],
+        bias: u64,
+        /// The virtual address, file offset and file size of each `PT_LOAD` segment.
+        segments: Vec<(u64, u64, u64)>,
+    }
+
+    impl<'a> FileImage<'a> {
+        fn new(bytes: &'a [u8], bias: u64) -> Option<FileImage<'a>> {
+            let file = FileImage { bytes, bias: 0, segments: Vec::new() };
+            // before the segments are known, addresses are file offsets
+            let segments = program_headers(&file, 0)?
+                .filter(|&header| file.read::<u32>(header) == Some(PT_LOAD))
+                .map(|header| {
+                    let [offset, vaddr] = file.read::<[u64; 2]>(header + 8)?;
+                    Some((vaddr, offset, file.read::<u64>(header + 0x20)?))
+                })
+                .collect::<Option<_>>()?;
+            Some(FileImage { bytes, bias, segments })
+        }
+
+        /// Where the file header is mapped, which is what `Elf::parse` takes as the base.
+        fn base(&self) -> Option<u64> {
+            self.segments.iter().find(|&&(_, offset, _)| offset == 0).map(|&(vaddr, _, _)| self.bias + vaddr)
+        }
+    }
+
+    impl Memory for FileImage<'_> {
+        fn read_bytes(&self, address: u64, buffer: &mut [u8]) -> Option<()> {
+            let length = buffer.len() as u64;
+            let offset = if self.segments.is_empty() {
+                address
+            } else {
+                let address = address.checked_sub(self.bias)?;
+                let &(vaddr, offset, _) = self
+                    .segments
+                    .iter()
+                    .find(|&&(vaddr, _, size)| address >= vaddr && address - vaddr + length <= size)?;
+                offset + (address - vaddr)
+            };
+            let start = usize::try_from(offset).ok()?;
+            buffer.copy_from_slice(self.bytes.get(start..start.checked_add(buffer.len())?)?);
+            Some(())
+        }
+    }
+
+    /// Where a library is loaded in the tests that apply a bias, like the loader would.
+    const LOAD_BIAS: u64 = 0x7f12_3456_7000;
+
+    fn parse(image: &FileImage) -> Elf {
+        Elf::parse(image, image.base().unwrap()).unwrap()
+    }
+
+    /// The shared libraries that the test process has mapped, by path.
+    fn mapped_libraries() -> Vec<String> {
+        let maps = std::fs::read_to_string("/proc/self/maps").unwrap_or_default();
+        let mut paths: Vec<String> = maps
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(5))
+            .filter(|path| path.starts_with('/') && path.contains(".so"))
+            .map(str::to_owned)
+            .collect();
+        paths.dedup();
+        paths
+    }
+
+    fn read_libc() -> Vec<u8> {
+        let libc = mapped_libraries()
+            .into_iter()
+            .find(|path| path.contains("libc.so") || path.contains("libc-"))
+            .expect("the test process has libc mapped");
+        std::fs::read(libc).unwrap()
+    }
+
+    /// A shared library exporting `exported` at 0x1000 and importing `imported`, with only a
+    /// sysv hash table. Its file offsets are its virtual addresses.
+    fn sysv_library() -> Vec<u8> {
+        const DYNAMIC: u64 = 0xb0;
+        const HASH: u64 = 0xf0;
+        const SYMTAB: u64 = 0x108;
+        const STRTAB: u64 = 0x150;
+        let strings = b"\0exported\0imported\0";
+        let mut bytes = vec![0u8; STRTAB as usize];
+        let mut put = |offset: u64, value: &[u8]| bytes[offset as usize..offset as usize + value.len()].copy_from_slice(value);
+        let size = STRTAB + strings.len() as u64;
+
+        put(0, b"\x7fELF\x02\x01\x01");
+        put(0x10, &3u16.to_le_bytes());
+        put(0x20, &0x40u64.to_le_bytes());
+        put(0x36, &56u16.to_le_bytes());
+        put(0x38, &2u16.to_le_bytes());
+        for (header, kind, offset, size) in [(0x40, PT_LOAD, 0, size), (0x78, PT_DYNAMIC, DYNAMIC, 0x40)] {
+            put(header, &kind.to_le_bytes());
+            put(header + 8, bytemuck::bytes_of(&[offset, offset, offset, size, size]));
+        }
+        put(DYNAMIC, bytemuck::bytes_of(&[DT_HASH, HASH, DT_STRTAB, STRTAB, DT_SYMTAB, SYMTAB, DT_NULL, 0]));
+        // one bucket, and a chain for each of the three symbols
+        put(HASH, bytemuck::bytes_of(&[1u32, 3, 0, 0, 0, 0]));
+        for (index, name, value) in [(1u64, 1u32, 0x1000u64), (2, 10, 0)] {
+            put(SYMTAB + index * SYMBOL_SIZE, &name.to_le_bytes());
+            put(SYMTAB + index * SYMBOL_SIZE + 8, &value.to_le_bytes());
+        }
+        bytes.extend_from_slice(strings);
+        bytes
+    }
+
+    #[test]
+    fn finds_exports_of_libraries_on_disk() {
+        let bytes = read_libc();
+        let image = FileImage::new(&bytes, 0).unwrap();
+        let elf = parse(&image);
+        assert!(elf.gnu_hash.is_some());
+        assert!(elf.symbol(&image, "malloc").is_some());
+        assert!(elf.symbol(&image, "free").is_some());
+        assert_eq!(elf.symbol(&image, "mono_get_root_domain"), None);
+    }
+
+    #[test]
+    fn finds_exports_without_the_gnu_hash_table() {
+        let bytes = read_libc();
+        let image = FileImage::new(&bytes, 0).unwrap();
+        let malloc = parse(&image).symbol(&image, "malloc");
+        assert!(malloc.is_some());
+        // without either hash table, the symbols are counted up to where .dynstr starts
+        let elf = Elf { gnu_hash: None, hash: None, ..parse(&image) };
+        assert_eq!(elf.symbol(&image, "malloc"), malloc);
+    }
+
+    #[test]
+    fn applies_the_load_bias() {
+        let bytes = read_libc();
+        let unbiased = FileImage::new(&bytes, 0).unwrap();
+        let biased = FileImage::new(&bytes, LOAD_BIAS).unwrap();
+        let malloc = parse(&unbiased).symbol(&unbiased, "malloc").unwrap();
+        assert_eq!(parse(&biased).symbol(&biased, "malloc"), Some(malloc + LOAD_BIAS));
+    }
+
+    #[test]
+    fn finds_exports_through_the_sysv_hash_table() {
+        let bytes = sysv_library();
+        for bias in [0, LOAD_BIAS] {
+            let image = FileImage::new(&bytes, bias).unwrap();
+            let elf = parse(&image);
+            assert_eq!((elf.hash, elf.gnu_hash), (Some(bias + 0xf0), None));
+            assert_eq!(elf.symbol(&image, "exported"), Some(bias + 0x1000));
+            assert_eq!(elf.symbol(&image, "imported"), None);
+            assert_eq!(elf.symbol(&image, "missing"), None);
+        }
+    }
+}
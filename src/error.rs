@@ -0,0 +1,40 @@
+use {crate::game_types::GameVersion, std::fmt};
+
+/// The step at which attaching to the game failed.
+#[derive(Clone, PartialEq, Eq)]
+pub enum AttachError {
+    ProcessNotFound,
+    DomainListUnreadable,
+    /// A system mono's root domain isn't laid out the way the bundled runtime's is.
+    SystemMonoLayout,
+    AssemblyMissing,
+    ClassNotFound(String),
+    FieldNotFound(String),
+    /// The field exists, but its object or its value couldn't be read.
+    FieldUnreadable(String),
+    /// A reference field that has to be set is null.
+    NullField(String),
+    /// Mono described a class with a kind that we don't know how to walk the fields of.
+    BadClassKind(u8),
+    /// Mono described a class with a field count that can't be right.
+    BadFieldCount(i32),
+    UnsupportedVersion(GameVersion),
+}
+
+impl fmt::Display for AttachError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttachError::ProcessNotFound => write!(f, "Celeste is not running"),
+            AttachError::DomainListUnreadable => write!(f, "could not read the runtime's domain list"),
+            AttachError::SystemMonoLayout => write!(f, "this system mono's layout doesn't match the bundled one"),
+            AttachError::AssemblyMissing => write!(f, "could not find the Celeste assembly"),
+            AttachError::ClassNotFound(name) => write!(f, "could not find the class {}", name),
+            AttachError::FieldNotFound(name) => write!(f, "could not find the field {}", name),
+            AttachError::FieldUnreadable(name) => write!(f, "could not read the field {}", name),
+            AttachError::NullField(name) => write!(f, "the field {} is null", name),
+            AttachError::BadClassKind(kind) => write!(f, "unexpected mono class kind {}", kind),
+            AttachError::BadFieldCount(count) => write!(f, "unexpected mono field count {}", count),
+            AttachError::UnsupportedVersion(version) => write!(f, "Celeste {} is not supported", version),
+        }
+    }
+}
@@ -6,6 +6,17 @@ pub struct Settings {
     _general_settings: Title,
     /// Use chapter timer (as opposed to file timer)
     pub level_time: bool,
+    /// Chapter completion splits for A-Sides
+    #[default = true]
+    pub complete_aside: bool,
+    /// Chapter completion splits for B-Sides
+    #[default = true]
+    pub complete_bside: bool,
+    /// Chapter completion splits for C-Sides
+    #[default = true]
+    pub complete_cside: bool,
+    /// Full clear (only split chapter completion with every strawberry collected)
+    pub full_clear: bool,
 
     /// General Splits
     _general_splits: Title,
@@ -38,6 +49,8 @@ pub struct Settings {
     pub epilogue: bool,
     /// Chapter 8 - Core A/B/C (Complete)
     pub chapter8: bool,
+    /// Chapter 9 - Farewell (Crystal Heart / Moon Berry)
+    pub chapter9: bool,
 
     /// Checkpoint Splits
     _checkpoint_splits: Title,
@@ -97,6 +110,22 @@ pub struct Settings {
     pub chapter8_checkpoint2: bool,
     /// Chapter 8 - Heart Of The Mountain (A) / Heartbeat (B) (CP 3)
     pub chapter8_checkpoint3: bool,
+    /// Chapter 9 - Singular (CP 1)
+    pub chapter9_checkpoint1: bool,
+    /// Chapter 9 - Power Source (CP 2)
+    pub chapter9_checkpoint2: bool,
+    /// Chapter 9 - Remembered (CP 3)
+    pub chapter9_checkpoint3: bool,
+    /// Chapter 9 - Event Horizon (CP 4)
+    pub chapter9_checkpoint4: bool,
+    /// Chapter 9 - Determination (CP 5)
+    pub chapter9_checkpoint5: bool,
+    /// Chapter 9 - Stubbornness (CP 6)
+    pub chapter9_checkpoint6: bool,
+    /// Chapter 9 - Reconciliation (CP 7)
+    pub chapter9_checkpoint7: bool,
+    /// Chapter 9 - Farewell (CP 8)
+    pub chapter9_checkpoint8: bool,
 
     /// Collectable Splits
     _collectable_splits: Title,
@@ -132,6 +161,81 @@ pub struct Settings {
     pub chapter8_cassette: bool,
     /// Chapter 8 - Heart Gem A/B/C (Pickup)
     pub chapter8_heartgem: bool,
+
+    /// Strawberry Splits
+    _strawberry_splits: Title,
+    /// Every Strawberry
+    pub strawberries_every1: bool,
+    /// Every 5 Strawberries
+    pub strawberries_every5: bool,
+    /// Every 10 Strawberries
+    pub strawberries_every10: bool,
+    /// 20 Strawberries
+    pub strawberries_20: bool,
+    /// 175 Strawberries
+    pub strawberries_175: bool,
+    /// 202 Strawberries
+    pub strawberries_202: bool,
+
+    /// Collectable Count Splits
+    _collectable_count_splits: Title,
+    /// Every Crystal Heart (File Total)
+    pub hearts_every1: bool,
+    /// 8 Crystal Hearts (File Total)
+    pub hearts_8: bool,
+    /// 16 Crystal Hearts (File Total)
+    pub hearts_16: bool,
+    /// 24 Crystal Hearts (File Total)
+    pub hearts_24: bool,
+    /// Every Cassette (File Total)
+    pub cassettes_every1: bool,
+    /// 8 Cassettes (File Total)
+    pub cassettes_8: bool,
+
+    /// Golden Berry Runs
+    _golden_berry_runs: Title,
+    /// Golden/Silver Berry (Pickup)
+    pub golden_split: bool,
+    /// Reset on death while carrying a golden/silver berry
+    pub golden_reset: bool,
+}
+
+impl Settings {
+    pub fn completion_enabled(&self, mode: AreaMode) -> bool {
+        match mode {
+            AreaMode::ASide => self.complete_aside,
+            AreaMode::BSide => self.complete_bside,
+            AreaMode::CSide => self.complete_cside,
+        }
+    }
+
+    pub fn cassette_enabled(&self, area: Area) -> bool {
+        match area {
+            Area::ForsakenCity => self.chapter1_cassette,
+            Area::OldSite => self.chapter2_cassette,
+            Area::CelestialResort => self.chapter3_cassette,
+            Area::GoldenRidge => self.chapter4_cassette,
+            Area::MirrorTemple => self.chapter5_cassette,
+            Area::Reflection => self.chapter6_cassette,
+            Area::TheSummit => self.chapter7_cassette,
+            Area::Core => self.chapter8_cassette,
+            _ => false,
+        }
+    }
+
+    pub fn heartgem_enabled(&self, area: Area) -> bool {
+        match area {
+            Area::ForsakenCity => self.chapter1_heartgem,
+            Area::OldSite => self.chapter2_heartgem,
+            Area::CelestialResort => self.chapter3_heartgem,
+            Area::GoldenRidge => self.chapter4_heartgem,
+            Area::MirrorTemple => self.chapter5_heartgem,
+            Area::Reflection => self.chapter6_heartgem,
+            Area::TheSummit => self.chapter7_heartgem,
+            Area::Core => self.chapter8_heartgem,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -148,6 +252,48 @@ pub enum Area {
     TheSummit = 7,
     Epilogue = 8,
     Core = 9,
+    Farewell = 10,
+}
+
+impl Area {
+    /// The number of strawberries needed for a full clear, not counting golden or moon berries.
+    pub fn strawberry_total(self, mode: AreaMode) -> Option<i32> {
+        if mode != AreaMode::ASide {
+            return Some(0);
+        }
+        Some(match self {
+            Area::Menu => return None,
+            Area::Prologue => 0,
+            Area::ForsakenCity => 20,
+            Area::OldSite => 18,
+            Area::CelestialResort => 25,
+            Area::GoldenRidge => 29,
+            Area::MirrorTemple => 31,
+            Area::Reflection => 0,
+            Area::TheSummit => 47,
+            Area::Epilogue => 0,
+            Area::Core => 5,
+            Area::Farewell => 0,
+        })
+    }
+
+    pub fn from_id(id: i32) -> Option<Area> {
+        Some(match id {
+            -1 => Area::Menu,
+            0 => Area::Prologue,
+            1 => Area::ForsakenCity,
+            2 => Area::OldSite,
+            3 => Area::CelestialResort,
+            4 => Area::GoldenRidge,
+            5 => Area::MirrorTemple,
+            6 => Area::Reflection,
+            7 => Area::TheSummit,
+            8 => Area::Epilogue,
+            9 => Area::Core,
+            10 => Area::Farewell,
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -157,6 +303,115 @@ pub enum AreaMode {
     CSide,
 }
 
+impl AreaMode {
+    pub fn from_id(id: i32) -> Option<AreaMode> {
+        Some(match id {
+            0 => AreaMode::ASide,
+            1 => AreaMode::BSide,
+            2 => AreaMode::CSide,
+            _ => return None,
+        })
+    }
+}
+
+/// A checkpoint split: entering `room` in the given side of `area` splits if `setting` is enabled.
+/// C-sides have no checkpoints, so there are deliberately no `AreaMode::CSide` entries.
+pub struct Checkpoint {
+    pub area: Area,
+    pub mode: AreaMode,
+    pub room: &'static str,
+    pub setting: fn(&Settings) -> bool,
+}
+
+pub const CHECKPOINTS: &[Checkpoint] = &[
+    Checkpoint { area: Area::ForsakenCity, mode: AreaMode::ASide, room: "6", setting: |s| s.chapter1_checkpoint1 },
+    Checkpoint { area: Area::ForsakenCity, mode: AreaMode::ASide, room: "9b", setting: |s| s.chapter1_checkpoint2 },
+    Checkpoint { area: Area::ForsakenCity, mode: AreaMode::BSide, room: "04", setting: |s| s.chapter1_checkpoint1 },
+    Checkpoint { area: Area::ForsakenCity, mode: AreaMode::BSide, room: "08", setting: |s| s.chapter1_checkpoint2 },
+    Checkpoint { area: Area::OldSite, mode: AreaMode::ASide, room: "3", setting: |s| s.chapter2_checkpoint1 },
+    Checkpoint { area: Area::OldSite, mode: AreaMode::ASide, room: "end_3", setting: |s| s.chapter2_checkpoint2 },
+    Checkpoint { area: Area::OldSite, mode: AreaMode::BSide, room: "03", setting: |s| s.chapter2_checkpoint1 },
+    Checkpoint { area: Area::OldSite, mode: AreaMode::BSide, room: "08b", setting: |s| s.chapter2_checkpoint2 },
+    Checkpoint { area: Area::CelestialResort, mode: AreaMode::ASide, room: "08-a", setting: |s| s.chapter3_checkpoint1 },
+    Checkpoint { area: Area::CelestialResort, mode: AreaMode::ASide, room: "09-d", setting: |s| s.chapter3_checkpoint2 },
+    Checkpoint { area: Area::CelestialResort, mode: AreaMode::ASide, room: "00-d", setting: |s| s.chapter3_checkpoint3 },
+    Checkpoint { area: Area::CelestialResort, mode: AreaMode::BSide, room: "06", setting: |s| s.chapter3_checkpoint1 },
+    Checkpoint { area: Area::CelestialResort, mode: AreaMode::BSide, room: "11", setting: |s| s.chapter3_checkpoint2 },
+    Checkpoint { area: Area::CelestialResort, mode: AreaMode::BSide, room: "16", setting: |s| s.chapter3_checkpoint3 },
+    Checkpoint { area: Area::GoldenRidge, mode: AreaMode::ASide, room: "b-00", setting: |s| s.chapter4_checkpoint1 },
+    Checkpoint { area: Area::GoldenRidge, mode: AreaMode::ASide, room: "c-00", setting: |s| s.chapter4_checkpoint2 },
+    Checkpoint { area: Area::GoldenRidge, mode: AreaMode::ASide, room: "d-00", setting: |s| s.chapter4_checkpoint3 },
+    Checkpoint { area: Area::GoldenRidge, mode: AreaMode::BSide, room: "b-00", setting: |s| s.chapter4_checkpoint1 },
+    Checkpoint { area: Area::GoldenRidge, mode: AreaMode::BSide, room: "c-00", setting: |s| s.chapter4_checkpoint2 },
+    Checkpoint { area: Area::GoldenRidge, mode: AreaMode::BSide, room: "d-00", setting: |s| s.chapter4_checkpoint3 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::ASide, room: "b-00", setting: |s| s.chapter5_checkpoint1 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::ASide, room: "c-00", setting: |s| s.chapter5_checkpoint2 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::ASide, room: "d-00", setting: |s| s.chapter5_checkpoint3 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::ASide, room: "e-00", setting: |s| s.chapter5_checkpoint4 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::BSide, room: "b-00", setting: |s| s.chapter5_checkpoint1 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::BSide, room: "c-00", setting: |s| s.chapter5_checkpoint2 },
+    Checkpoint { area: Area::MirrorTemple, mode: AreaMode::BSide, room: "d-00", setting: |s| s.chapter5_checkpoint3 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::ASide, room: "00", setting: |s| s.chapter6_checkpoint1 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::ASide, room: "04", setting: |s| s.chapter6_checkpoint2 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::ASide, room: "b-00", setting: |s| s.chapter6_checkpoint3 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::ASide, room: "boss-00", setting: |s| s.chapter6_checkpoint4 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::ASide, room: "after-00", setting: |s| s.chapter6_checkpoint5 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::BSide, room: "b-00", setting: |s| s.chapter6_checkpoint1 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::BSide, room: "c-00", setting: |s| s.chapter6_checkpoint2 },
+    Checkpoint { area: Area::Reflection, mode: AreaMode::BSide, room: "d-00", setting: |s| s.chapter6_checkpoint3 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::ASide, room: "b-00", setting: |s| s.chapter7_checkpoint1 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::ASide, room: "c-00", setting: |s| s.chapter7_checkpoint2 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::ASide, room: "d-00", setting: |s| s.chapter7_checkpoint3 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::ASide, room: "e-00b", setting: |s| s.chapter7_checkpoint4 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::ASide, room: "f-00", setting: |s| s.chapter7_checkpoint5 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::ASide, room: "g-00", setting: |s| s.chapter7_checkpoint6 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::BSide, room: "b-00", setting: |s| s.chapter7_checkpoint1 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::BSide, room: "c-01", setting: |s| s.chapter7_checkpoint2 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::BSide, room: "d-00", setting: |s| s.chapter7_checkpoint3 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::BSide, room: "e-00", setting: |s| s.chapter7_checkpoint4 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::BSide, room: "f-00", setting: |s| s.chapter7_checkpoint5 },
+    Checkpoint { area: Area::TheSummit, mode: AreaMode::BSide, room: "g-00", setting: |s| s.chapter7_checkpoint6 },
+    Checkpoint { area: Area::Core, mode: AreaMode::ASide, room: "a-00", setting: |s| s.chapter8_checkpoint1 },
+    Checkpoint { area: Area::Core, mode: AreaMode::ASide, room: "c-00", setting: |s| s.chapter8_checkpoint2 },
+    Checkpoint { area: Area::Core, mode: AreaMode::ASide, room: "d-00", setting: |s| s.chapter8_checkpoint3 },
+    Checkpoint { area: Area::Core, mode: AreaMode::BSide, room: "a-00", setting: |s| s.chapter8_checkpoint1 },
+    Checkpoint { area: Area::Core, mode: AreaMode::BSide, room: "b-00", setting: |s| s.chapter8_checkpoint2 },
+    Checkpoint { area: Area::Core, mode: AreaMode::BSide, room: "c-01", setting: |s| s.chapter8_checkpoint3 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "a-00", setting: |s| s.chapter9_checkpoint1 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "c-00", setting: |s| s.chapter9_checkpoint2 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "e-00z", setting: |s| s.chapter9_checkpoint3 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "f-door", setting: |s| s.chapter9_checkpoint4 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "h-00b", setting: |s| s.chapter9_checkpoint5 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "i-00", setting: |s| s.chapter9_checkpoint6 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "j-00", setting: |s| s.chapter9_checkpoint7 },
+    Checkpoint { area: Area::Farewell, mode: AreaMode::ASide, room: "j-16", setting: |s| s.chapter9_checkpoint8 },
+];
+
+/// A release of the game, as `major.minor.build.revision`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GameVersion(pub i32, pub i32, pub i32, pub i32);
+
+impl GameVersion {
+    /// The oldest release whose AutoSplitterInfo this splitter understands.
+    const OLDEST_SUPPORTED: GameVersion = GameVersion(1, 2, 0, 0);
+    /// The release that added Farewell.
+    const FAREWELL: GameVersion = GameVersion(1, 3, 0, 0);
+
+    pub fn is_supported(self) -> bool {
+        self >= GameVersion::OLDEST_SUPPORTED && self.0 == 1
+    }
+
+    pub fn has_farewell(self) -> bool {
+        self >= GameVersion::FAREWELL
+    }
+}
+
+impl std::fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.0, self.1, self.2, self.3)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 #[repr(i32)]
 pub enum Menu {
@@ -168,3 +423,80 @@ pub enum Menu {
     ChapterPanel = 168,
     FileRename = 180,
 }
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Threshold {
+    Every(i32),
+    Exactly(i32),
+}
+
+impl Threshold {
+    /// Whether a counter going from `last` to `current` reaches this threshold.
+    pub fn crossed(self, last: i32, current: i32) -> bool {
+        if current <= last {
+            return false;
+        }
+        match self {
+            Threshold::Every(n) => current.div_euclid(n) > last.div_euclid(n),
+            Threshold::Exactly(n) => last < n && n <= current,
+        }
+    }
+}
+
+/// A file-wide counter split: reaching `threshold` splits if `setting` is enabled.
+pub struct CounterSplit {
+    pub threshold: Threshold,
+    pub setting: fn(&Settings) -> bool,
+}
+
+pub const STRAWBERRY_SPLITS: &[CounterSplit] = &[
+    CounterSplit { threshold: Threshold::Every(1), setting: |s| s.strawberries_every1 },
+    CounterSplit { threshold: Threshold::Every(5), setting: |s| s.strawberries_every5 },
+    CounterSplit { threshold: Threshold::Every(10), setting: |s| s.strawberries_every10 },
+    CounterSplit { threshold: Threshold::Exactly(20), setting: |s| s.strawberries_20 },
+    CounterSplit { threshold: Threshold::Exactly(175), setting: |s| s.strawberries_175 },
+    CounterSplit { threshold: Threshold::Exactly(202), setting: |s| s.strawberries_202 },
+];
+
+pub const HEART_SPLITS: &[CounterSplit] = &[
+    CounterSplit { threshold: Threshold::Every(1), setting: |s| s.hearts_every1 },
+    CounterSplit { threshold: Threshold::Exactly(8), setting: |s| s.hearts_8 },
+    CounterSplit { threshold: Threshold::Exactly(16), setting: |s| s.hearts_16 },
+    CounterSplit { threshold: Threshold::Exactly(24), setting: |s| s.hearts_24 },
+];
+
+pub const CASSETTE_SPLITS: &[CounterSplit] = &[
+    CounterSplit { threshold: Threshold::Every(1), setting: |s| s.cassettes_every1 },
+    CounterSplit { threshold: Threshold::Exactly(8), setting: |s| s.cassettes_8 },
+];
+
+/// The names of the player's `StateMachine` states, indexed by state. Everest's custom states
+/// come after these.
+pub const PLAYER_STATES: &[&str] = &[
+    "Normal",
+    "Climb",
+    "Dash",
+    "Swim",
+    "Boost",
+    "Red Dash",
+    "Hit Squash",
+    "Launch",
+    "Pickup",
+    "Dream Dash",
+    "Summit Launch",
+    "Dummy",
+    "Intro Walk",
+    "Intro Jump",
+    "Intro Respawn",
+    "Intro Wake Up",
+    "Bird Dash Tutorial",
+    "Frozen",
+    "Reflection Fall",
+    "Star Fly",
+    "Temple Fall",
+    "Cassette Fly",
+    "Attract",
+    "Intro Moon Jump",
+    "Fling Bird",
+    "Intro Think For A Bit",
+];
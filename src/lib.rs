@@ -1,27 +1,122 @@
+mod coreclr;
+mod elf;
+mod error;
 mod game_types;
+mod managed;
+mod mono;
+mod object_model;
+mod path;
+mod player;
+mod save_data;
+mod session;
 
 use {
-    crate::game_types::{Area, AreaMode, Settings}, asr::{print_limited, settings::Gui, string::ArrayCString, time::Duration, timer::{pause_game_time, reset, set_game_time, set_variable, split, start}, Error, Process}, bytemuck::Pod, static_locks::{MappedMutexGuard, Mutex, MutexGuard}
+    crate::{coreclr::CoreClr, error::AttachError, game_types::{Area, AreaMode, CounterSplit, GameVersion, Settings, CASSETTE_SPLITS, CHECKPOINTS, HEART_SPLITS, PLAYER_STATES, STRAWBERRY_SPLITS}, mono::{Mono, MonoModule}, object_model::{field, ObjectModel}, player::{Player, PlayerReader}, save_data::{SaveData, SaveDataReader}, session::{Session, SessionReader}}, asr::{print_limited, settings::Gui, time::Duration, timer::{pause_game_time, reset, set_game_time, set_variable, split, start}, Error, Process, ProcessId}, bytemuck::Pod, static_locks::{MappedMutexGuard, Mutex, MutexGuard}
 };
 
 static STATE: Mutex<Option<Celeste>> = Mutex::new(None);
 
+struct Pickups {
+    cassette: bool,
+    heart: bool,
+    berry: bool,
+}
+
+/// Tracks a file-wide counter between ticks so that threshold splits fire once per crossing.
+#[derive(Default)]
+struct Counter {
+    last: Option<i32>,
+}
+
+impl Counter {
+    fn update(&mut self, current: Option<i32>, splits: &[CounterSplit], settings: &Settings) -> bool {
+        let last = std::mem::replace(&mut self.last, current);
+        match (last, current) {
+            (Some(last), Some(current)) => splits
+                .iter()
+                .any(|split| split.threshold.crossed(last, current) && (split.setting)(settings)),
+            _ => false,
+        }
+    }
+}
+
+/// The offsets of AutoSplitterInfo's fields from the start of the object.
+struct AsiLayout {
+    level: u64,
+    chapter: u64,
+    mode: u64,
+    chapter_started: u64,
+    chapter_complete: u64,
+    chapter_time: u64,
+    chapter_strawberries: u64,
+    chapter_cassette: u64,
+    chapter_heart: u64,
+    file_time: u64,
+    file_strawberries: u64,
+    /// Not every release has the file-wide collectable counters.
+    file_cassettes: Option<u64>,
+    file_hearts: Option<u64>,
+}
+
+impl AsiLayout {
+    /// Looks up every field by name, failing on the first one that is missing.
+    fn resolve(model: &dyn ObjectModel, process: &Process, class: u64) -> Result<AsiLayout, AttachError> {
+        let offset = |name| model.field_offset(process, class, name);
+        Ok(AsiLayout {
+            level: offset("Level")?,
+            chapter: offset("Chapter")?,
+            mode: offset("Mode")?,
+            chapter_started: offset("ChapterStarted")?,
+            chapter_complete: offset("ChapterComplete")?,
+            chapter_time: offset("ChapterTime")?,
+            chapter_strawberries: offset("ChapterStrawberries")?,
+            chapter_cassette: offset("ChapterCassette")?,
+            chapter_heart: offset("ChapterHeart")?,
+            file_time: offset("FileTime")?,
+            file_strawberries: offset("FileStrawberries")?,
+            file_cassettes: offset("FileCassettes").ok(),
+            file_hearts: offset("FileHearts").ok(),
+        })
+    }
+}
+
 struct Celeste {
     process: Process,
     settings: Settings,
+    model: Box<dyn ObjectModel + Send>,
+    layout: AsiLayout,
+    asi_class: u64,
     asi_base: u64,
+    version: Option<GameVersion>,
     last_completed: bool,
     exiting_chapter: bool,
     last_level: String,
     reset_level: bool,
+    last_cassette: bool,
+    last_heart: bool,
+    last_chapter_strawberries: i32,
+    strawberry_count: Counter,
+    heart_count: Counter,
+    cassette_count: Counter,
+    last_room: Option<(i32, String)>,
+    session_reader: SessionReader,
+    session: Option<Session>,
+    last_session: Option<Session>,
+    /// Whether the chapter was restarted after dying with a golden since `last_session`.
+    golden_restart: bool,
+    player_reader: PlayerReader,
+    player: Option<Player>,
+    golden_attempts: u32,
+    save_data_reader: SaveDataReader,
+    save_data: Option<SaveData>,
 }
 
 impl Celeste {
     fn sanity_check(&self) -> bool {
-        if self.process.read::<u32>(self.asi_base + 0x14).ok() != Some(0) {
+        if self.model.instance_class(&self.process, self.asi_base) != Some(self.asi_class) {
             return false;
         }
-        if let Some(ptr) = self.process.read::<u32>(self.asi_base + 0x00).ok() {
+        if let Some(ptr) = self.process.read::<u32>(self.asi_base + self.layout.level).ok() {
             if ptr == 0 {
                 return false;
             }
@@ -38,21 +133,66 @@ impl Celeste {
     }
 
     fn chapter_completed(&self) -> bool {
-        self.readbool(self.asi_base + 0x12).unwrap_or(false)
+        self.readbool(self.asi_base + self.layout.chapter_complete).unwrap_or(false)
+    }
+
+    /// Rereads the save file and publishes its statistics. The per-area stats only change when
+    /// the player dies, collects a heart or cassette, or finishes a chapter, so they are only
+    /// reread then.
+    fn update_save_data(&mut self) {
+        let previous = self.save_data.take();
+        let Some(mut save) = self.save_data_reader.read(&*self.model, &self.process) else {
+            return;
+        };
+        set_variable("File Name", &save.name);
+        set_variable("Total Deaths", &save.total_deaths.to_string());
+        set_variable("Total Dashes", &save.total_dashes.to_string());
+        set_variable("Unlocked Areas", &save.unlocked_areas.to_string());
+
+        // the `last_` flags are still the previous tick's, so these are the ticks they change on
+        let just_completed = self.chapter_completed() && !self.last_completed;
+        let just_collected = (self.chapter_heart() && !self.last_heart) || (self.chapter_cassette() && !self.last_cassette);
+        let previously_unreadable = previous.as_ref().is_some_and(|previous| previous.areas.is_none());
+        let stale = match &previous {
+            Some(previous) => previously_unreadable || previous.address != save.address || previous.total_deaths != save.total_deaths || just_completed || just_collected,
+            None => true,
+        };
+        save.areas = match previous {
+            Some(previous) if !stale => previous.areas,
+            _ => self.save_data_reader.read_areas(&*self.model, &self.process, &save),
+        };
+        // a file whose stats couldn't be read is retried every tick, so only its first failure is logged
+        if save.areas.is_none() && !previously_unreadable {
+            print_limited::<128>(&format_args!("Could not read the area stats of {}", save.name));
+        }
+        if stale {
+            for area in save.areas.iter().flatten() {
+                for (mode, stats) in area.modes.iter().enumerate() {
+                    let name = format!("Chapter {}{}", area.id, ["A", "B", "C"].get(mode).unwrap_or(&"?"));
+                    // the cassette is kept per chapter rather than per side
+                    let cassette = mode == 0 && area.cassette;
+                    let completion = [(stats.completed, "complete"), (stats.full_clear, "full clear"), (stats.heart_gem, "heart"), (cassette, "cassette")]
+                        .iter()
+                        .filter(|(done, _)| *done)
+                        .map(|(_, label)| *label)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    set_variable(&format!("{} Best Time", name), &format!("{:.2}", stats.best_time));
+                    set_variable(&format!("{} Best Full Clear Time", name), &format!("{:.2}", stats.best_full_clear_time));
+                    set_variable(&format!("{} Deaths", name), &stats.deaths.to_string());
+                    set_variable(&format!("{} Completion", name), &completion);
+                }
+            }
+        }
+        self.save_data = Some(save);
     }
 
     fn level_name_init(&self) -> Option<String> {
-        let level_ptr = self.read::<u64>(self.asi_base)?;
+        let level_ptr = self.read::<u64>(self.asi_base + self.layout.level)?;
         if level_ptr == 0 {
             return None;
         }
-        let size = self.read::<u32>(level_ptr + 0x10)?;
-        if size > 512 {
-            return None;
-        }
-        let mut buffer = vec![0u16; size as usize];
-        self.process.read_into_slice(level_ptr + 0x14, &mut buffer).ok()?;
-        String::from_utf16(&buffer).ok()
+        self.model.read_string(&self.process, level_ptr)
     }
 
     fn level_name(&self) -> String {
@@ -60,7 +200,7 @@ impl Celeste {
     }
 
     fn area_id_fallible(&self) -> Result<i32, Error> {
-        self.process.read::<i32>(self.asi_base + 0x8)
+        self.process.read::<i32>(self.asi_base + self.layout.chapter)
     }
 
     fn area_id(&self) -> i32 {
@@ -68,43 +208,58 @@ impl Celeste {
     }
 
     fn area_difficulty(&self) -> i32 {
-        self.read(self.asi_base + 0xc).unwrap_or(-1)
+        self.read(self.asi_base + self.layout.mode).unwrap_or(-1)
     }
 
     fn chapter_started(&self) -> bool {
-        self.readbool(self.asi_base + 0x11).unwrap_or(false)
+        self.readbool(self.asi_base + self.layout.chapter_started).unwrap_or(false)
     }
 
     fn game_time(&self) -> Duration {
-        Duration::milliseconds(self.read::<i64>(self.asi_base + 0x28).unwrap_or(0) / 10000)
+        Duration::milliseconds(self.read::<i64>(self.asi_base + self.layout.file_time).unwrap_or(0) / 10000)
     }
 
     fn level_time(&self) -> Duration {
-        Duration::milliseconds(self.read::<i64>(self.asi_base + 0x18).unwrap_or(0) / 10000)
+        Duration::milliseconds(self.read::<i64>(self.asi_base + self.layout.chapter_time).unwrap_or(0) / 10000)
     }
 
     fn file_strawberries(&self) -> i32 {
-        self.read(self.asi_base + 0x30).unwrap_or(0)
+        self.read(self.asi_base + self.layout.file_strawberries).unwrap_or(0)
     }
 
     fn chapter_strawberries(&self) -> i32 {
-        self.read(self.asi_base + 0x20).unwrap_or(0)
+        self.read(self.asi_base + self.layout.chapter_strawberries).unwrap_or(0)
     }
 
-    fn file_cassettes(&self) -> i32 {
-        self.read(self.asi_base + 0x34).unwrap_or(0)
+    fn file_cassettes(&self) -> Option<i32> {
+        self.read(self.asi_base + self.layout.file_cassettes?)
     }
 
-    fn file_hearts(&self) -> i32 {
-        self.read(self.asi_base + 0x38).unwrap_or(0)
+    fn file_hearts(&self) -> Option<i32> {
+        self.read(self.asi_base + self.layout.file_hearts?)
     }
 
     fn chapter_cassette(&self) -> bool {
-        self.readbool(self.asi_base + 0x24).unwrap_or(false)
+        self.readbool(self.asi_base + self.layout.chapter_cassette).unwrap_or(false)
     }
 
     fn chapter_heart(&self) -> bool {
-        self.readbool(self.asi_base + 0x25).unwrap_or(false)
+        self.readbool(self.asi_base + self.layout.chapter_heart).unwrap_or(false)
+    }
+
+    fn side_enabled(&self) -> bool {
+        AreaMode::from_id(self.area_difficulty())
+            .map(|mode| self.settings.completion_enabled(mode))
+            .unwrap_or(false)
+    }
+
+    fn strawberry_total(&self, area_id: i32) -> Option<i32> {
+        Area::from_id(area_id)?.strawberry_total(AreaMode::from_id(self.area_difficulty())?)
+    }
+
+    fn missing_strawberries(&self, area_id: i32) -> Option<i32> {
+        let total = self.strawberry_total(area_id)?;
+        Some((total - self.chapter_strawberries()).max(0))
     }
 
     fn chapter_split(
@@ -124,127 +279,349 @@ impl Celeste {
             self.exiting_chapter = area_id == chapter_area as i32
                 && completed
                 && !self.last_completed
-                && not_in_credits;
+                && not_in_credits
+                && self.side_enabled()
+                && (!self.settings.full_clear || self.missing_strawberries(self.area_id()) == Some(0));
             self.exiting_chapter && il_splits
         } else {
             !completed && self.last_completed
         }
     }
-}
 
-fn class_field_offset(process: &Process, klass: u64, name: &str) -> Option<u64> {
-    let class_kind = process.read::<u8>(klass + 0x24).ok()? & 7;
-    if class_kind == 3 {
-        return class_field_offset(process, process.read::<u64>(process.read::<u64>(klass + 0xe0).ok()?).ok()?, name);
+    fn checkpoint_split(&self, area_id: i32, level: &str) -> bool {
+        let area = match Area::from_id(area_id) {
+            Some(area) => area,
+            None => return false,
+        };
+        let mode = match AreaMode::from_id(self.area_difficulty()) {
+            Some(mode) => mode,
+            None => return false,
+        };
+        CHECKPOINTS
+            .iter()
+            .find(|checkpoint| checkpoint.area == area && checkpoint.mode == mode && checkpoint.room == level)
+            .is_some_and(|checkpoint| (checkpoint.setting)(&self.settings))
     }
-    if class_kind != 1 && class_kind != 2 {
-        panic!();
+
+    fn pickups(&mut self) -> Pickups {
+        let cassette = self.chapter_cassette();
+        let heart = self.chapter_heart();
+        let berries = self.chapter_strawberries();
+        let pickups = Pickups {
+            cassette: cassette && !self.last_cassette,
+            heart: heart && !self.last_heart,
+            berry: berries > self.last_chapter_strawberries,
+        };
+        self.last_cassette = cassette;
+        self.last_heart = heart;
+        self.last_chapter_strawberries = berries;
+        pickups
     }
 
-    let num_fields = process.read::<i32>(klass + 0xf0).ok()?;
-    let fields_ptr = process.read::<u64>(klass + 0x90).ok()?;
+    fn collectable_split(&self, area_id: i32, pickups: &Pickups) -> bool {
+        let area = match Area::from_id(area_id) {
+            Some(area) => area,
+            None => return false,
+        };
+        (pickups.cassette && self.settings.cassette_enabled(area))
+            || (pickups.heart && self.settings.heartgem_enabled(area))
+    }
 
-    let mut fields_buf = vec![0u64; num_fields as usize * 4];
-    process.read_into_slice(fields_ptr, &mut fields_buf).ok()?;
+    fn farewell_split(&self, area_id: i32, pickups: &Pickups) -> bool {
+        // farewell has no chapter complete screen. it ends on the final crystal heart, or on the
+        // moon berry, which is the only strawberry in the chapter
+        self.version.is_none_or(GameVersion::has_farewell)
+            && area_id == Area::Farewell as i32
+            && (pickups.heart || pickups.berry)
+            && self.side_enabled()
+    }
 
-    for arr in fields_buf.chunks(4) {
-        let field_name_ptr = arr[1];
-        let field_offset = arr[3] & 0xffff_ffff;
-        if process.read::<ArrayCString::<256>>(field_name_ptr).ok()?.matches(name) {
-            return Some(field_offset);
+    fn counter_split(&mut self, level: &str) -> bool {
+        // the file counters jump around while picking a save file, so only count pickups in a level
+        let in_level = !level.is_empty();
+        let strawberries = Some(self.file_strawberries()).filter(|_| in_level);
+        let hearts = self.file_hearts().filter(|_| in_level);
+        let cassettes = self.file_cassettes().filter(|_| in_level);
+        let mut should_split = false;
+        should_split |= self.strawberry_count.update(strawberries, STRAWBERRY_SPLITS, &self.settings);
+        should_split |= self.heart_count.update(hearts, HEART_SPLITS, &self.settings);
+        should_split |= self.cassette_count.update(cassettes, CASSETTE_SPLITS, &self.settings);
+        should_split
+    }
+
+    /// Rereads the active level's session and publishes it.
+    fn update_session(&mut self) {
+        let session = self.session_reader.read(&*self.model, &self.process);
+        // a restart only counts until the next level has been compared against the last one
+        if self.session.is_some() {
+            self.golden_restart = false;
+        }
+        if session.is_none() {
+            self.golden_restart |= self.session_reader.golden_restart(&*self.model, &self.process);
+        }
+        if let Some(session) = &session {
+            set_variable("Session Deaths", &session.deaths.to_string());
+            set_variable("Session Dashes", &session.dashes.to_string());
+            let respawn_point = session.respawn_point.map(|(x, y)| format!("{:.0}, {:.0}", x, y));
+            set_variable("Respawn Point", respawn_point.as_deref().unwrap_or(""));
+            set_variable("Start Checkpoint", session.start_checkpoint.as_deref().unwrap_or("start"));
+            set_variable("Current Checkpoint", session.checkpoint.as_deref().unwrap_or("start"));
+            set_variable("Session Flags", &session.flags.as_ref().map_or_else(|| "unreadable".to_owned(), |flags| flags.join(", ")));
+        }
+        // the last session is kept through the loading scenes between levels, so that coming back
+        // from one is still compared against the level that was left
+        if let Some(last) = std::mem::replace(&mut self.session, session) {
+            self.last_session = Some(last);
         }
     }
-    None
-}
 
+    /// Rereads the player entity and publishes it. There is only a player while in a level.
+    fn update_player(&mut self) {
+        self.player = if self.session.is_some() { self.player_reader.read(&*self.model, &self.process) } else { None };
+        if let Some(player) = &self.player {
+            set_variable("Position", &format!("{:.1}, {:.1}", player.position.0, player.position.1));
+            set_variable("Speed", &format!("{:.1}, {:.1}", player.speed.0, player.speed.1));
+            set_variable("Dashes", &player.dashes.to_string());
+            set_variable("Stamina", &format!("{:.1}", player.stamina));
+            let state = usize::try_from(player.state).ok().and_then(|state| PLAYER_STATES.get(state));
+            set_variable("Player State", &state.map_or_else(|| player.state.to_string(), |name| name.to_string()));
+        }
+    }
 
-fn lookup_class(process: &Process, class_cache: u64, name: &str) -> Option<u64> {
-    let celeste_class_cache_table = process.read::<u32>(class_cache + 0x20).ok()?;
-    let hash_table_size = process.read::<u32>(class_cache + 0x18).ok()?;
-    for bucket in 0..hash_table_size {
-        let mut klass = process.read::<u64>(celeste_class_cache_table + 8*bucket).ok()?;
-        while klass != 0 {
-            let current_name_ptr = process.read::<u64>(klass + 0x40).ok()?;
-            let name_arr = process.read::<ArrayCString<128>>(current_name_ptr).ok()?;
-            if name_arr.matches(name) {
-                return Some(klass);
-            }
-            klass = process.read::<u64>(klass + 0xf8).ok()?;
+    /// Whether the player just died or restarted, and so went back to their respawn point.
+    fn respawned(&self) -> bool {
+        match (&self.session, &self.last_session) {
+            (Some(session), Some(last)) => session.address != last.address || session.deaths > last.deaths,
+            _ => false,
         }
     }
-    None
-}
 
-fn class_static_fields(process: &Process, klass: u64) -> Option<u64> {
-    let runtime_info = process.read::<u64>(klass + 0xc8).ok()?;
-    let celeste_vtable = process.read::<u64>(runtime_info + 8).ok()?;
-    let vtable_size = process.read::<u32>(klass + 0x54).ok()? as u64;
-    process.read(celeste_vtable + 64 + vtable_size * 8).ok()
-}
+    /// Whether the player just died, which restarts the chapter if they were carrying a golden.
+    /// Leaving a completed chapter for another isn't a death, whatever was carried.
+    fn died(&self) -> bool {
+        match (&self.session, &self.last_session) {
+            (Some(_), Some(last)) if last.completed => false,
+            (Some(session), Some(last)) if session.address == last.address => session.deaths > last.deaths,
+            (Some(_), Some(_)) => self.golden_restart,
+            _ => false,
+        }
+    }
 
-fn instance_class(process: &Process, instance: u64) -> Option<u64> {
-    process.read(process.read::<u64>(instance).ok()? & 0xffff_ffff_ffff_fffe).ok()
-}
+    /// Returns whether a golden or silver berry was just picked up, resetting the timer if the
+    /// player died while carrying one.
+    fn golden_split(&mut self) -> bool {
+        if !self.settings.golden_split && !self.settings.golden_reset {
+            return false;
+        }
+        let (session, last) = match (&self.session, &self.last_session) {
+            (Some(session), Some(last)) => (session, last),
+            _ => return false,
+        };
 
-fn field<T: Pod>(process: &Process, instance: u64, name: &str) -> Option<T> {
-    let klass = instance_class(process, instance)?;
-    let offset = class_field_offset(process, klass, name)?;
-    process.read(offset + instance).ok()
-}
+        // dying with a golden restarts the chapter with a fresh session
+        if last.grabbed_golden && self.died() && self.settings.golden_reset {
+            reset();
+        }
 
-fn static_field<T: Pod>(process: &Process, klass: u64, name: &str) -> Option<T> {
-    let offset = class_field_offset(process, klass, name)?;
-    let static_ptr = class_static_fields(process, klass)?;
-    process.read(offset + static_ptr).ok()
-}
+        let grabbed = session.grabbed_golden && (!last.grabbed_golden || session.address != last.address);
+        if grabbed {
+            self.golden_attempts += 1;
+            set_variable("Golden Attempts", &self.golden_attempts.to_string());
+        }
+        grabbed && self.settings.golden_split
+    }
 
-fn find_base() -> Option<Celeste> {
-    let process = Process::attach("Celeste.bin.x86_64")?;
-    //let mono_addr = process.get_module_address("Celeste.bin.x86_64").ok()?;
-    //let mono_root_domain = mono_addr + 0xA17650;
-    let domains_list = process.read::<u64>(0xA17698u64).ok()?;
+    fn room_split(&mut self, area_id: i32, level: &str) -> bool {
+        // the level name is blank while loading, so only a new nonempty name is a transition
+        if level.is_empty() {
+            return false;
+        }
+        if let Some((last_area, last_level)) = &self.last_room {
+            if *last_area == area_id && last_level == level {
+                return false;
+            }
+        }
+        // leaving a room is only an exit if another room of the same chapter is entered
+        let exited = matches!(&self.last_room, Some((last_area, _)) if *last_area == area_id);
+        self.last_room = Some((area_id, level.to_owned()));
+        // coming back to a checkpoint after dying or restarting isn't entering a room
+        if self.respawned() {
+            return false;
+        }
+        self.settings.level_enter || (self.settings.level_exit && exited)
+    }
+}
 
-    let first_domain = process.read::<u64>(domains_list).ok()?;
-    let second_domain = process.read::<u64>(domains_list + 8).ok()?;
-    let first_domain_name = if first_domain == 0 { None } else { Some(process.read::<ArrayCString<128>>(process.read::<u64>(first_domain + 0xd8).ok()?).ok()?) };
-    let second_domain_name = if second_domain == 0 { None } else { Some(process.read::<ArrayCString<128>>(process.read::<u64>(second_domain + 0xd8).ok()?).ok()?) };
+/// Reads the `System.Version` in `Celeste.Instance.Version`. Not every runtime can look up the
+/// core library's classes, but its four fields are laid out in order in all of them.
+fn read_version(model: &dyn ObjectModel, process: &Process, celeste_obj: u64) -> Option<GameVersion> {
+    let version = field::<u64, _>(model, process, celeste_obj, "Version").ok().filter(|&ptr| ptr != 0)?;
+    let by_name = || {
+        Some(GameVersion(
+            field(model, process, version, "_Major").ok()?,
+            field(model, process, version, "_Minor").ok()?,
+            field(model, process, version, "_Build").ok()?,
+            field(model, process, version, "_Revision").ok()?,
+        ))
+    };
+    by_name().or_else(|| {
+        let [major, minor, build, revision] = managed::read_fields::<[i32; 4]>(process, &model.layout(), version)?;
+        Some(GameVersion(major, minor, build, revision))
+    })
+}
 
-    if first_domain_name.map(|s| s.matches("Celeste.exe")) != Some(true) {
-        return None;
+/// Processes running a release that isn't supported, which aren't attached to again for as long
+/// as they keep running.
+static UNSUPPORTED_PROCESSES: Mutex<Vec<(ProcessId, GameVersion)>> = Mutex::new(Vec::new());
+
+/// Processes that didn't have the game's assembly or a runtime we can read, with how many more
+/// attaches skip them. A `mono` process may well be another program, but the game may also not
+/// have loaded its assembly yet, so they are only tried less often rather than given up on.
+static FAILED_PROCESSES: Mutex<Vec<(ProcessId, AttachError, u32)>> = Mutex::new(Vec::new());
+
+const FAILED_ATTACH_COOLDOWN: u32 = 600;
+
+fn find_base() -> Result<Celeste, AttachError> {
+    // everest's own launcher is called Celeste, but it may also be started as the vanilla binary,
+    // and packagers run Celeste.exe with the system mono, which other programs may be running on
+    let pids: Vec<ProcessId> = ["Celeste.bin.x86_64", "Celeste", "mono-sgen", "mono"]
+        .into_iter()
+        .flat_map(|name| Process::list_by_name(name).into_iter().flatten())
+        .collect();
+    let mut unsupported = UNSUPPORTED_PROCESSES.lock();
+    unsupported.retain(|(pid, _)| pids.contains(pid));
+    let mut failed = FAILED_PROCESSES.lock();
+    failed.retain(|(pid, _, _)| pids.contains(pid));
+    mono::forget_processes(&pids);
+    coreclr::forget_processes(&pids);
+
+    let mut error = AttachError::ProcessNotFound;
+    for pid in pids {
+        if let Some(&(_, version)) = unsupported.iter().find(|(unsupported_pid, _)| *unsupported_pid == pid) {
+            error = AttachError::UnsupportedVersion(version);
+            continue;
+        }
+        if let Some((_, failed_error, skips)) = failed.iter_mut().find(|(failed_pid, _, _)| *failed_pid == pid) {
+            if *skips > 0 {
+                *skips -= 1;
+                error = failed_error.clone();
+                continue;
+            }
+        }
+        failed.retain(|(failed_pid, _, _)| *failed_pid != pid);
+        let Some(process) = Process::attach_by_pid(pid) else {
+            continue;
+        };
+        match attach(pid, process) {
+            Ok(celeste) => return Ok(celeste),
+            Err(AttachError::UnsupportedVersion(version)) => {
+                unsupported.push((pid, version));
+                error = AttachError::UnsupportedVersion(version);
+            }
+            Err(attach_error @ (AttachError::AssemblyMissing | AttachError::SystemMonoLayout)) => {
+                failed.push((pid, attach_error.clone(), FAILED_ATTACH_COOLDOWN));
+                error = attach_error;
+            }
+            Err(attach_error) => error = attach_error,
+        }
     }
+    Err(error)
+}
 
-    let celeste_domain = if second_domain != 0 {
-        print_limited::<128>(&format_args!("Connected to {} (domain 2)", second_domain_name.unwrap().validate_utf8().unwrap()));
-        second_domain
+fn attach(pid: ProcessId, process: Process) -> Result<Celeste, AttachError> {
+    let model: Box<dyn ObjectModel + Send> = if process.get_module_address("libcoreclr.so").is_ok() {
+        Box::new(CoreClr::attach(&process, pid)?)
+    } else if let Ok(mono) = MonoModule::attach(&process, pid) {
+        Box::new(mono)
     } else {
-        print_limited::<128>(&format_args!("Connected to {} (domain 1)", first_domain_name.unwrap().validate_utf8().unwrap()));
-        first_domain
+        Box::new(Mono::attach(&process, pid)?)
     };
 
-    let celeste_assembly = process.read::<u64>(celeste_domain + 0xd0).ok()?;
-    let celeste_image = process.read::<u64>(celeste_assembly + 0x60).ok()?;
-    let class_cache = celeste_image + 1216;
-
-    let celeste_class = lookup_class(&process, class_cache, "Celeste")?;
-    let celeste_obj = static_field::<u64>(&process, celeste_class, "Instance")?;
-    let autosplitter_obj = field::<u64>(&process, celeste_obj, "AutoSplitterInfo")? + 0x10;
+    let celeste_class = model.lookup_class(&process, "Celeste")?;
+    let celeste_obj = model.static_object(&process, celeste_class, "Instance")?;
+    let version = read_version(&*model, &process, celeste_obj);
+    match version {
+        Some(version) if !version.is_supported() => {
+            set_variable("Game Version", &format!("{} (unsupported)", version));
+            return Err(AttachError::UnsupportedVersion(version));
+        }
+        Some(version) => set_variable("Game Version", &version.to_string()),
+        None => set_variable("Game Version", "unknown"),
+    }
+    let asi_base = field::<u64, _>(&*model, &process, celeste_obj, "AutoSplitterInfo")?;
+    if asi_base == 0 {
+        return Err(AttachError::NullField("AutoSplitterInfo".to_owned()));
+    }
+    let asi_class = model
+        .instance_class(&process, asi_base)
+        .ok_or_else(|| AttachError::ClassNotFound("AutoSplitterInfo".to_owned()))?;
+    let layout = AsiLayout::resolve(&*model, &process, asi_class)?;
 
     let settings = Settings::register();
-    return Some(Celeste {
+    let mut celeste = Celeste {
         process,
         settings,
-        asi_base: autosplitter_obj,
+        model,
+        layout,
+        asi_class,
+        asi_base,
+        version,
         last_completed: false,
         exiting_chapter: false,
         last_level: "".to_owned(),
         reset_level: false,
-    });
+        last_cassette: false,
+        last_heart: false,
+        last_chapter_strawberries: 0,
+        strawberry_count: Counter::default(),
+        heart_count: Counter::default(),
+        cassette_count: Counter::default(),
+        last_room: None,
+        session_reader: SessionReader::new(),
+        session: None,
+        last_session: None,
+        golden_restart: false,
+        player_reader: PlayerReader::new(),
+        player: None,
+        save_data_reader: SaveDataReader::new(),
+        save_data: None,
+        golden_attempts: 0,
+    };
+    // attaching mid-chapter mustn't count what was collected before as just picked up, or the
+    // current room as just entered
+    celeste.pickups();
+    let level = celeste.level_name();
+    if !level.is_empty() {
+        celeste.last_room = Some((celeste.area_id(), level));
+    }
+    print_limited::<128>(&format_args!("Connected to Celeste ({})", celeste.model.runtime()));
+    Ok(celeste)
+}
+
+/// The error of the last failed attach, so that retrying every tick doesn't flood the log.
+static ATTACH_ERROR: Mutex<Option<AttachError>> = Mutex::new(None);
+
+fn report_attach(error: Option<&AttachError>) {
+    let mut last = ATTACH_ERROR.lock();
+    if error.is_some() && last.as_ref() == error {
+        return;
+    }
+    match error {
+        Some(error) => {
+            print_limited::<128>(&format_args!("Could not attach: {}", error));
+            set_variable("Attach Status", &error.to_string());
+        }
+        None => set_variable("Attach Status", "Attached"),
+    }
+    *last = error.cloned();
 }
 
 fn state() -> Option<MappedMutexGuard<'static, Celeste>> {
     let mut state = STATE.lock();
     if state.is_none() {
-        *state = find_base();
+        let result = find_base();
+        report_attach(result.as_ref().err());
+        *state = result.ok();
     }
     MutexGuard::try_map(state, |x| x.as_mut()).ok()
 }
@@ -272,6 +649,9 @@ fn update_inner() -> bool {
             pause_game_time();
         }
         state.reset_level = reset_level;
+        state.update_save_data();
+        state.update_session();
+        state.update_player();
         if should_split(&mut state) {
             split()
         }
@@ -284,6 +664,11 @@ fn should_split(state: &mut Celeste) -> bool {
     let area_id = state.area_id();
     let level_name = state.level_name();
     set_variable("Level", &level_name);
+    let room_split = state.room_split(area_id, &level_name);
+    let counter_split = state.counter_split(&level_name);
+    let golden_split = state.golden_split();
+    let pickups = state.pickups();
+    let farewell_complete = state.farewell_split(area_id, &pickups);
     let level_name = if level_name == state.last_level {
         "".to_owned()
     } else {
@@ -291,6 +676,12 @@ fn should_split(state: &mut Celeste) -> bool {
         level_name
     };
 
+    if state.settings.full_clear && completed && !state.last_completed {
+        if let Some(missing) = state.missing_strawberries(area_id).filter(|&missing| missing > 0) {
+            print_limited::<128>(&format_args!("Not a full clear: missed {} strawberries", missing));
+        }
+    }
+
     let mut should_split = false;
     let lt = state.settings.level_time;
 
@@ -301,6 +692,7 @@ fn should_split(state: &mut Celeste) -> bool {
             completed,
             lt,
         );
+    should_split |= state.settings.chapter && farewell_complete;
     should_split |= state.settings.prologue && state.chapter_split(area_id, Area::Prologue, &level_name, completed, lt);
     should_split |= state.settings.chapter1 && state.chapter_split(area_id, Area::ForsakenCity, &level_name, completed, lt);
     should_split |= state.settings.chapter2 && state.chapter_split(area_id, Area::OldSite, &level_name, completed, lt);
@@ -311,118 +703,12 @@ fn should_split(state: &mut Celeste) -> bool {
     should_split |= state.settings.chapter7 && state.chapter_split(area_id, Area::TheSummit, &level_name, completed, lt);
     should_split |= state.settings.epilogue && state.chapter_split(area_id, Area::Epilogue, &level_name, completed, lt);
     should_split |= state.settings.chapter8 && state.chapter_split(area_id, Area::Core, &level_name, completed, lt);
-    should_split |= state.settings.chapter1_checkpoint1 && area_id == Area::ForsakenCity as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"6"[..]
-                    } else {
-                        &"04"[..]
-                    };
-    should_split |= state.settings.chapter1_checkpoint2 && area_id == Area::ForsakenCity as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"9b"[..]
-                    } else {
-                        &"08"[..]
-                    };
-    should_split |= state.settings.chapter2_checkpoint1 && area_id == Area::OldSite as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"3"[..]
-                    } else {
-                        &"03"[..]
-                    };
-    should_split |= state.settings.chapter2_checkpoint2 && area_id == Area::OldSite as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"end_3"[..]
-                    } else {
-                        &"08b"[..]
-                    };
-    should_split |= state.settings.chapter3_checkpoint1 && area_id == Area::CelestialResort as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"08-a"[..]
-                    } else {
-                        &"06"[..]
-                    };
-    should_split |= state.settings.chapter3_checkpoint2 && area_id == Area::CelestialResort as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"09-d"[..]
-                    } else {
-                        &"11"[..]
-                    };
-    should_split |= state.settings.chapter3_checkpoint3 && area_id == Area::CelestialResort as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"00-d"[..]
-                    } else {
-                        &"16"[..]
-                    };
-    should_split |= state.settings.chapter4_checkpoint1 && area_id == Area::GoldenRidge as i32 && level_name == "b-00";
-    should_split |= state.settings.chapter4_checkpoint2 && area_id == Area::GoldenRidge as i32 && level_name == "c-00";
-    should_split |= state.settings.chapter4_checkpoint3 && area_id == Area::GoldenRidge as i32 && level_name == "d-00";
-    should_split |= state.settings.chapter5_checkpoint1 && area_id == Area::MirrorTemple as i32 && level_name == "b-00";
-    should_split |= state.settings.chapter5_checkpoint2 && area_id == Area::MirrorTemple as i32 && level_name == "c-00";
-    should_split |= state.settings.chapter5_checkpoint3 && area_id == Area::MirrorTemple as i32 && level_name == "d-00";
-    should_split |= state.settings.chapter5_checkpoint4 && area_id == Area::MirrorTemple as i32 && level_name == "e-00";
-    should_split |= state.settings.chapter6_checkpoint1 && area_id == Area::Reflection as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"00"[..]
-                    } else {
-                        &"b-00"[..]
-                    };
-    should_split |= state.settings.chapter6_checkpoint2 && area_id == Area::Reflection as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"04"[..]
-                    } else {
-                        &"c-00"[..]
-                    };
-    should_split |= state.settings.chapter6_checkpoint3 && area_id == Area::Reflection as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"b-00"[..]
-                    } else {
-                        &"d-00"[..]
-                    };
-    should_split |= state.settings.chapter6_checkpoint4 && area_id == Area::Reflection as i32 && level_name == "boss-00";
-    should_split |= state.settings.chapter6_checkpoint5 && area_id == Area::Reflection as i32 && level_name == "after-00";
-    should_split |= state.settings.chapter7_checkpoint1 && area_id == Area::TheSummit as i32 && level_name == "b-00";
-    should_split |= state.settings.chapter7_checkpoint2 && area_id == Area::TheSummit as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"c-00"[..]
-                    } else {
-                        &"c-01"[..]
-                    };
-    should_split |= state.settings.chapter7_checkpoint3 && area_id == Area::TheSummit as i32 && level_name == "d-00";
-    should_split |= state.settings.chapter7_checkpoint4 && area_id == Area::TheSummit as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"e-00b"[..]
-                    } else {
-                        &"e-00"[..]
-                    };
-    should_split |= state.settings.chapter7_checkpoint5 && area_id == Area::TheSummit as i32 && level_name == "f-00";
-    should_split |= state.settings.chapter7_checkpoint6 && area_id == Area::TheSummit as i32 && level_name == "g-00";
-    should_split |= state.settings.chapter8_checkpoint1 && area_id == Area::Core as i32 && level_name == "a-00";
-    should_split |= state.settings.chapter8_checkpoint2 && area_id == Area::Core as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"c-00"[..]
-                    } else {
-                        &"b-00"[..]
-                    };
-    should_split |= state.settings.chapter8_checkpoint3 && area_id == Area::Core as i32
-                && level_name
-                    == if state.area_difficulty() == AreaMode::ASide as i32 {
-                        &"d-00"[..]
-                    } else {
-                        &"c-01"[..]
-                    };
+    should_split |= state.settings.chapter9 && farewell_complete;
+    should_split |= state.checkpoint_split(area_id, &level_name);
+    should_split |= state.collectable_split(area_id, &pickups);
+    should_split |= room_split;
+    should_split |= counter_split;
+    should_split |= golden_split;
 
     state.last_completed = completed;
 
@@ -437,6 +723,9 @@ fn should_split(state: &mut Celeste) -> bool {
 
 fn game_time(state: &mut Celeste) -> Duration {
     set_variable("Strawberries", &state.file_strawberries().to_string());
+    if let Some(total) = state.strawberry_total(state.area_id()) {
+        set_variable("Chapter Strawberries", &format!("{} / {}", state.chapter_strawberries(), total));
+    }
     set_variable("Level Timer", &format!("{:.2}", state.level_time()));
     let elapsed = if state.settings.level_time {
         state.level_time()
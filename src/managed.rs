@@ -0,0 +1,178 @@
+//! Readers for the built-in managed types that the game keeps its state in: strings, arrays and
+//! the generic collections. Every length read from the game is checked before it is used to size
+//! a read, since a stale pointer can make it anything.
+
+use {
+    crate::object_model::{field, ObjectModel},
+    asr::Process,
+    bytemuck::{pod_read_unaligned, Pod},
+    std::mem::{align_of, size_of},
+};
+
+/// Level names and the like are short, so anything longer is garbage.
+const MAX_STRING_LENGTH: u32 = 512;
+const MAX_ARRAY_LENGTH: u64 = 0x10_0000;
+
+/// Where a runtime keeps the contents of strings and single-dimensional arrays.
+#[derive(Copy, Clone)]
+pub struct Layout {
+    string_length: u64,
+    string_chars: u64,
+    array_length: u64,
+    array_data: u64,
+    /// Where an object's own fields start, after its header.
+    object_fields: u64,
+}
+
+/// After the vtable and sync pointers, arrays also have a pointer to their bounds.
+pub const MONO: Layout = Layout { string_length: 0x10, string_chars: 0x14, array_length: 0x18, array_data: 0x20, object_fields: 0x10 };
+
+/// The sync block index lives before an object's method table pointer rather than after it.
+pub const CORECLR: Layout = Layout { string_length: 0x8, string_chars: 0xc, array_length: 0x8, array_data: 0x10, object_fields: 0x8 };
+
+pub fn read_string(process: &Process, layout: &Layout, string: u64) -> Option<String> {
+    let length = process.read::<u32>(string + layout.string_length).ok()?;
+    if length > MAX_STRING_LENGTH {
+        return None;
+    }
+    let mut buffer = vec![0u16; length as usize];
+    process.read_into_slice(string + layout.string_chars, &mut buffer).ok()?;
+    String::from_utf16(&buffer).ok()
+}
+
+/// Reads the fields of an object as one value, for types whose classes can't be looked up but
+/// whose layout is the same everywhere, like `System.Version`.
+pub fn read_fields<T: Pod>(process: &Process, layout: &Layout, object: u64) -> Option<T> {
+    process.read(object + layout.object_fields).ok()
+}
+
+fn array_length(process: &Process, layout: &Layout, array: u64) -> Option<u64> {
+    if array == 0 {
+        return None;
+    }
+    let length = process.read::<u32>(array + layout.array_length).ok()? as u64;
+    (length <= MAX_ARRAY_LENGTH).then_some(length)
+}
+
+/// The first `count` elements of an array, as raw bytes with the given stride.
+fn read_array_bytes(process: &Process, layout: &Layout, array: u64, stride: usize, count: Option<u64>) -> Option<Vec<u8>> {
+    let length = array_length(process, layout, array)?;
+    let count = match count {
+        Some(count) if count > length => return None,
+        Some(count) => count,
+        None => length,
+    };
+    let mut buffer = vec![0u8; count as usize * stride];
+    process.read_into_slice(array + layout.array_data, &mut buffer).ok()?;
+    Some(buffer)
+}
+
+/// Reads a `T[]`, where `T` is a primitive, or `u64` for references.
+pub fn read_array<T: Pod>(process: &Process, layout: &Layout, array: u64) -> Option<Vec<T>> {
+    let bytes = read_array_bytes(process, layout, array, size_of::<T>(), None)?;
+    Some(bytes.chunks_exact(size_of::<T>()).map(pod_read_unaligned::<T>).collect())
+}
+
+/// Reads a `List<T>`, whose backing array may be longer than the list.
+pub fn read_list<T: Pod, M: ObjectModel + ?Sized>(model: &M, process: &Process, list: u64) -> Option<Vec<T>> {
+    let items = field::<u64, _>(model, process, list, "_items").ok()?;
+    let size = field::<i32, _>(model, process, list, "_size").ok()?;
+    let bytes = read_array_bytes(process, &model.layout(), items, size_of::<T>(), Some(u64::try_from(size).ok()?))?;
+    Some(bytes.chunks_exact(size_of::<T>()).map(pod_read_unaligned::<T>).collect())
+}
+
+fn align(offset: usize, alignment: usize) -> usize {
+    offset.next_multiple_of(alignment)
+}
+
+/// The hash tables of the collections below all store entries of a hash code and a next index,
+/// followed by their contents. Returns the offset of each value in an entry and the entry size.
+fn entry_layout(values: &[(usize, usize)]) -> (Vec<usize>, usize) {
+    let mut offsets = Vec::new();
+    let mut end = 8;
+    let mut max_alignment = 4;
+    for &(size, alignment) in values {
+        let offset = align(end, alignment);
+        offsets.push(offset);
+        end = offset + size;
+        max_alignment = max_alignment.max(alignment);
+    }
+    (offsets, align(end, max_alignment))
+}
+
+/// How to tell which entries of a hash table are in use, which differs between implementations.
+#[derive(Copy, Clone)]
+enum Occupied {
+    /// Free entries have a negative hash code.
+    HashCode,
+    /// Free entries have a next index below -1, which encodes the free list.
+    Next,
+}
+
+impl Occupied {
+    fn check(self, entry: &[u8]) -> bool {
+        let hash_code = pod_read_unaligned::<i32>(&entry[0..4]);
+        let next = pod_read_unaligned::<i32>(&entry[4..8]);
+        match self {
+            Occupied::HashCode => hash_code >= 0,
+            Occupied::Next => next >= -1,
+        }
+    }
+}
+
+/// Reads the used entries of a hash table, given the names its implementation uses for the entry
+/// array and the number of entries ever used.
+fn read_entries<M: ObjectModel + ?Sized>(
+    model: &M,
+    process: &Process,
+    table: u64,
+    variants: &[(&str, &str, Occupied)],
+    values: &[(usize, usize)],
+) -> Option<(Vec<Vec<u8>>, Vec<usize>)> {
+    let class = model.instance_class(process, table)?;
+    let &(entries_name, count_name, occupied) = variants.iter().find(|(entries, _, _)| model.field_offset(process, class, entries).is_ok())?;
+    let entries = field::<u64, _>(model, process, table, entries_name).ok()?;
+    let count = field::<i32, _>(model, process, table, count_name).ok()?;
+    if count == 0 {
+        return Some((Vec::new(), Vec::new()));
+    }
+
+    let (offsets, stride) = entry_layout(values);
+    let bytes = read_array_bytes(process, &model.layout(), entries, stride, Some(u64::try_from(count).ok()?))?;
+    let used = bytes.chunks_exact(stride).filter(|entry| occupied.check(entry)).map(<[u8]>::to_vec).collect();
+    Some((used, offsets))
+}
+
+/// Reads a `HashSet<T>`, in no particular order.
+pub fn read_hash_set<T: Pod, M: ObjectModel + ?Sized>(model: &M, process: &Process, set: u64) -> Option<Vec<T>> {
+    let variants = [
+        // mono's copy of the corefx implementation
+        ("_slots", "_lastIndex", Occupied::HashCode),
+        // .NET 5 and later
+        ("_entries", "_count", Occupied::Next),
+    ];
+    let (entries, offsets) = read_entries(model, process, set, &variants, &[(size_of::<T>(), align_of::<T>())])?;
+    Some(entries.iter().map(|entry| pod_read_unaligned(&entry[offsets[0]..offsets[0] + size_of::<T>()])).collect())
+}
+
+/// Reads a `Dictionary<K, V>`, in no particular order.
+pub fn read_dictionary<K: Pod, V: Pod, M: ObjectModel + ?Sized>(model: &M, process: &Process, dictionary: u64) -> Option<Vec<(K, V)>> {
+    let variants = [
+        // the reference source, which mono uses
+        ("entries", "count", Occupied::HashCode),
+        // .NET 5 and later
+        ("_entries", "_count", Occupied::Next),
+    ];
+    let values = [(size_of::<K>(), align_of::<K>()), (size_of::<V>(), align_of::<V>())];
+    let (entries, offsets) = read_entries(model, process, dictionary, &variants, &values)?;
+    Some(
+        entries
+            .iter()
+            .map(|entry| {
+                let key = pod_read_unaligned(&entry[offsets[0]..offsets[0] + size_of::<K>()]);
+                let value = pod_read_unaligned(&entry[offsets[1]..offsets[1] + size_of::<V>()]);
+                (key, value)
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,560 @@
+//! Walking the structures of the mono runtime, either the one the vanilla game is bundled with or
+//! a system install running `mono Celeste.exe`.
+//!
+//! asr's own mono support is tried first. The raw offsets below are those of the bundled runtime,
+//! and are only used when asr doesn't recognize the process.
+
+use {
+    crate::{
+        elf::Elf,
+        error::AttachError,
+        managed::{self, Layout},
+        object_model::{ObjectModel, ProcessCaches},
+    },
+    asr::{game_engine::unity::mono, string::ArrayCString, Process, ProcessId},
+    static_locks::Mutex,
+    std::{
+        cell::{Cell, RefCell},
+        collections::BTreeMap,
+    },
+};
+
+/// More fields than any class in the game has, so a larger count is a misread class.
+const MAX_FIELDS: i32 = 4096;
+
+/// Far more buckets than the class cache of any image the game loads grows to.
+const MAX_CLASS_CACHE_SIZE: u32 = 0x10000;
+
+/// Finds a field's offset along with the address of its name, which identifies the field.
+fn find_field(process: &Process, klass: u64, name: &str) -> Result<(u64, u64), AttachError> {
+    let not_found = || AttachError::FieldNotFound(name.to_owned());
+    let class_kind = process.read::<u8>(klass + 0x24).map_err(|_| not_found())? & 7;
+    if class_kind == 3 {
+        let generic_class = process.read::<u64>(klass + 0xe0).map_err(|_| not_found())?;
+        return find_field(process, process.read::<u64>(generic_class).map_err(|_| not_found())?, name);
+    }
+    if class_kind != 1 && class_kind != 2 {
+        return Err(AttachError::BadClassKind(class_kind));
+    }
+
+    let num_fields = process.read::<i32>(klass + 0xf0).map_err(|_| not_found())?;
+    if !(0..=MAX_FIELDS).contains(&num_fields) {
+        return Err(AttachError::BadFieldCount(num_fields));
+    }
+    let fields_ptr = process.read::<u64>(klass + 0x90).map_err(|_| not_found())?;
+
+    let mut fields_buf = vec![0u64; num_fields as usize * 4];
+    process.read_into_slice(fields_ptr, &mut fields_buf).map_err(|_| not_found())?;
+
+    for arr in fields_buf.chunks(4) {
+        let field_name_ptr = arr[1];
+        let field_offset = arr[3] & 0xffff_ffff;
+        if process.read::<ArrayCString::<256>>(field_name_ptr).map_err(|_| not_found())?.matches(name) {
+            return Ok((field_offset, field_name_ptr));
+        }
+    }
+
+    // inherited fields live on the parent class
+    let parent = process.read::<u64>(klass + 0x28).map_err(|_| not_found())?;
+    if parent != 0 {
+        return find_field(process, parent, name);
+    }
+    Err(not_found())
+}
+
+/// Classes and fields resolved by earlier attaches to a process, by image and by class. `STATE`
+/// is reset whenever a sanity check fails, and reattaching then only has to check that each cached
+/// entry still has the name it was found by, rather than rescanning.
+#[derive(Default)]
+struct Resolved {
+    classes: BTreeMap<(u64, String), u64>,
+    fields: BTreeMap<(u64, String), (u64, u64)>,
+}
+
+static RESOLVED: ProcessCaches<Resolved> = ProcessCaches::new();
+
+/// Covers the caches of both `Mono` and `MonoModule`.
+pub fn forget_processes(pids: &[ProcessId]) {
+    RESOLVED.forget_processes(pids);
+    MODULE_CACHES.forget_processes(pids);
+}
+
+fn cached_lookup_class(process: &Process, pid: ProcessId, class_cache: u64, name: &str) -> Option<u64> {
+    let key = (class_cache, name.to_owned());
+    RESOLVED.with(pid, |resolved| {
+        if let Some(&klass) = resolved.classes.get(&key) {
+            if class_name(process, klass).is_some_and(|class_name| class_name.matches(name)) {
+                return Some(klass);
+            }
+            resolved.classes.remove(&key);
+        }
+        let klass = lookup_class(process, class_cache, name)?;
+        resolved.classes.insert(key, klass);
+        Some(klass)
+    })
+}
+
+fn class_field_offset(process: &Process, pid: ProcessId, klass: u64, name: &str) -> Result<u64, AttachError> {
+    let key = (klass, name.to_owned());
+    RESOLVED.with(pid, |resolved| {
+        if let Some(&(offset, name_ptr)) = resolved.fields.get(&key) {
+            if process.read::<ArrayCString<256>>(name_ptr).is_ok_and(|field_name| field_name.matches(name)) {
+                return Ok(offset);
+            }
+            resolved.fields.remove(&key);
+        }
+        let (offset, name_ptr) = find_field(process, klass, name)?;
+        resolved.fields.insert(key, (offset, name_ptr));
+        Ok(offset)
+    })
+}
+
+fn class_name(process: &Process, klass: u64) -> Option<ArrayCString<128>> {
+    let name_ptr = process.read::<u64>(klass + 0x40).ok()?;
+    process.read(name_ptr).ok()
+}
+
+fn lookup_class(process: &Process, class_cache: u64, name: &str) -> Option<u64> {
+    let celeste_class_cache_table = process.read::<u64>(class_cache + 0x20).ok()?;
+    let hash_table_size = process.read::<u32>(class_cache + 0x18).ok()?;
+    // anything that isn't an image has garbage here, which mustn't be walked bucket by bucket
+    if hash_table_size == 0 || hash_table_size > MAX_CLASS_CACHE_SIZE {
+        return None;
+    }
+    let mut buckets = vec![0u64; hash_table_size as usize];
+    process.read_into_slice(celeste_class_cache_table, &mut buckets).ok()?;
+    for mut klass in buckets {
+        while klass != 0 {
+            let current_name_ptr = process.read::<u64>(klass + 0x40).ok()?;
+            let name_arr = process.read::<ArrayCString<128>>(current_name_ptr).ok()?;
+            if name_arr.matches(name) {
+                return Some(klass);
+            }
+            klass = process.read::<u64>(klass + 0xf8).ok()?;
+        }
+    }
+    None
+}
+
+fn class_static_fields(process: &Process, klass: u64) -> Option<u64> {
+    let runtime_info = process.read::<u64>(klass + 0xc8).ok()?;
+    let celeste_vtable = process.read::<u64>(runtime_info + 8).ok()?;
+    let vtable_size = process.read::<u32>(klass + 0x54).ok()? as u64;
+    process.read(celeste_vtable + 64 + vtable_size * 8).ok()
+}
+
+fn instance_class(process: &Process, instance: u64) -> Option<u64> {
+    process.read(process.read::<u64>(instance).ok()? & 0xffff_ffff_ffff_fffe).ok()
+}
+
+/// The scan of a process's main module for mono's `appdomains_list`.
+struct DomainsListScan {
+    pid: ProcessId,
+    /// Addresses referenced by rip-relative loads, any of which might be the list, or `None` if
+    /// the module couldn't be read.
+    candidates: Option<Vec<u64>>,
+    /// The candidate that turned out to be the list, once one has.
+    found: Option<u64>,
+    /// How many more calls return nothing before the candidates are all checked again.
+    skips: u32,
+}
+
+/// Scanning the module is slow, so this is only done once per game launch, whatever it finds.
+static DOMAINS_LIST_SCAN: Mutex<Option<DomainsListScan>> = Mutex::new(None);
+
+/// The address of `appdomains_list` in the build this splitter was written against.
+const KNOWN_DOMAINS_LIST: u64 = 0xA17698;
+
+/// How many calls skip rechecking the candidates after none of them was the list. The list is
+/// only filled in once the runtime has created its domains, and checking every candidate on
+/// every tick until then would stall the timer.
+const DOMAINS_LIST_RECHECK_COOLDOWN: u32 = 600;
+
+fn is_domains_list(process: &Process, address: u64) -> bool {
+    let first_domain_name = (|| {
+        let domains_list = process.read::<u64>(address).ok().filter(|&ptr| ptr != 0)?;
+        let first_domain = process.read::<u64>(domains_list).ok().filter(|&ptr| ptr != 0)?;
+        process.read::<ArrayCString<128>>(process.read::<u64>(first_domain + 0xd8).ok()?).ok()
+    })();
+    first_domain_name.map(|s| s.matches("Celeste.exe")) == Some(true)
+}
+
+fn scan_domains_list(process: &Process) -> Option<Vec<u64>> {
+    let (base, size) = process.get_module_range("Celeste.bin.x86_64").ok()?;
+    let base = base.value();
+    let mut image = vec![0u8; size as usize];
+    for (i, page) in image.chunks_mut(0x1000).enumerate() {
+        // unmapped gaps between segments are left zeroed
+        let _ = process.read_into_slice(base + i as u64 * 0x1000, page);
+    }
+
+    // mov r64, qword ptr [rip + disp32]
+    let mut candidates = Vec::new();
+    for (i, window) in image.windows(7).enumerate() {
+        if window[0] & 0xfb != 0x48 || window[1] != 0x8b || window[2] & 0xc7 != 0x05 {
+            continue;
+        }
+        let disp = i32::from_le_bytes([window[3], window[4], window[5], window[6]]);
+        let target = (base + i as u64 + 7).wrapping_add(disp as i64 as u64);
+        // .bss is an anonymous mapping just past the module's file-backed range
+        if target >= base && target - base < size * 2 {
+            candidates.push(target);
+        }
+    }
+    candidates.sort_unstable();
+    candidates.dedup();
+    Some(candidates)
+}
+
+fn find_domains_list(process: &Process, pid: ProcessId) -> Option<u64> {
+    if is_domains_list(process, KNOWN_DOMAINS_LIST) {
+        return Some(KNOWN_DOMAINS_LIST);
+    }
+    let mut scan = DOMAINS_LIST_SCAN.lock();
+    if !scan.as_ref().is_some_and(|scan| scan.pid == pid) {
+        *scan = Some(DomainsListScan { pid, candidates: scan_domains_list(process), found: None, skips: 0 });
+    }
+    let scan = scan.as_mut()?;
+    if let Some(found) = scan.found.filter(|&found| is_domains_list(process, found)) {
+        return Some(found);
+    }
+    if scan.skips > 0 {
+        scan.skips -= 1;
+        return None;
+    }
+    scan.found = scan.candidates.as_ref()?.iter().copied().find(|&candidate| is_domains_list(process, candidate));
+    if scan.found.is_none() {
+        scan.skips = DOMAINS_LIST_RECHECK_COOLDOWN;
+    }
+    scan.found
+}
+
+/// The names a system mono's shared library may be loaded under.
+const SHARED_MONO_MODULES: [&str; 2] = ["libmonosgen-2.0.so.1", "libmonosgen-2.0.so"];
+
+/// Finds the global that a getter like `mono_get_root_domain` returns. These compile to a single
+/// rip-relative load, which goes through the GOT when the global isn't hidden.
+fn getter_target(process: &Process, function: u64) -> Option<u64> {
+    let mut start = function;
+    // endbr64
+    if process.read::<[u8; 4]>(start).ok()? == [0xf3, 0x0f, 0x1e, 0xfa] {
+        start += 4;
+    }
+    let code = process.read::<[u8; 10]>(start).ok()?;
+    // mov rax, qword ptr [rip + disp32]
+    if code[..3] != [0x48, 0x8b, 0x05] {
+        return None;
+    }
+    let disp = i32::from_le_bytes([code[3], code[4], code[5], code[6]]);
+    let slot = (start + 7).wrapping_add(disp as i64 as u64);
+    // mov rax, qword ptr [rax]
+    if code[7..10] == [0x48, 0x8b, 0x00] {
+        process.read(slot).ok()
+    } else {
+        Some(slot)
+    }
+}
+
+/// The root domain, if the module mapped at `base` exports mono's embedding api. For a system
+/// mono, this is the domain that `mono Celeste.exe` loads the game into.
+fn exported_root_domain(process: &Process, base: u64) -> Option<u64> {
+    let getter = Elf::parse(process, base)?.symbol(process, "mono_get_root_domain")?;
+    process.read::<u64>(getter_target(process, getter)?).ok().filter(|&ptr| ptr != 0)
+}
+
+fn domain_name(process: &Process, domain: u64) -> Option<ArrayCString<128>> {
+    process.read(process.read::<u64>(domain + 0xd8).ok()?).ok()
+}
+
+/// Whether something looks like a `MonoDomain` laid out the way the raw offsets expect: its
+/// friendly name is that of the assembly it was created for.
+fn is_domain(process: &Process, domain: u64) -> bool {
+    let name = domain_name(process, domain);
+    let name = name.as_ref().and_then(|name| name.validate_utf8().ok());
+    name.is_some_and(|name| name.ends_with(".exe") || name.ends_with(".dll"))
+}
+
+/// `appdomains_list` grows as domains are created and doesn't record its length, so this is as far
+/// as it is walked.
+const MAX_DOMAINS: u64 = 32;
+
+/// The live domains of the bundled runtime, in creation order.
+fn bundled_domains(process: &Process, pid: ProcessId) -> Option<Vec<u64>> {
+    let Some(domains_list) = find_domains_list(process, pid) else {
+        // without the list, the root domain is the best we can do if the binary exports it
+        let root_domain = exported_root_domain(process, process.get_module_address("Celeste.bin.x86_64").ok()?.value())?;
+        return Some(vec![root_domain]);
+    };
+    let domains_list = process.read::<u64>(domains_list).ok()?;
+
+    // unloading a domain empties its slot without moving the ones after it, so empty slots and
+    // anything else that isn't a domain are skipped
+    Some(
+        (0..MAX_DOMAINS)
+            .filter_map(|i| process.read::<u64>(domains_list + i * 8).ok())
+            .filter(|&domain| domain != 0 && is_domain(process, domain))
+            .collect(),
+    )
+}
+
+/// The domains of a system mono. Only the root domain can be found through its exports, which
+/// is the one that `mono Celeste.exe` loads the game into.
+///
+/// The raw offsets are those of the bundled runtime, and a system mono may be a different version
+/// with different layouts, so the root domain is only used if it passes `is_domain`. Past that,
+/// every class is checked by name before it's used.
+fn shared_domains(process: &Process) -> Result<Vec<u64>, AttachError> {
+    let base = SHARED_MONO_MODULES
+        .iter()
+        .find_map(|name| process.get_module_address(name).ok())
+        .ok_or(AttachError::DomainListUnreadable)?;
+    let root_domain = exported_root_domain(process, base.value()).ok_or(AttachError::DomainListUnreadable)?;
+    if !is_domain(process, root_domain) {
+        return Err(AttachError::SystemMonoLayout);
+    }
+    Ok(vec![root_domain])
+}
+
+/// The assemblies loaded into a domain, starting with the one it was created to run.
+fn domain_assemblies(process: &Process, domain: u64) -> Vec<u64> {
+    let mut assemblies = Vec::new();
+    if let Ok(entry_assembly) = process.read::<u64>(domain + 0xd0) {
+        assemblies.push(entry_assembly);
+    }
+    // a GSList of data and next pointers
+    let mut node = process.read::<u64>(domain + 0xc8).unwrap_or(0);
+    while node != 0 && assemblies.len() < 1024 {
+        let Ok([assembly, next]) = process.read::<[u64; 2]>(node) else {
+            break;
+        };
+        assemblies.push(assembly);
+        node = next;
+    }
+    assemblies.retain(|&assembly| assembly != 0);
+    assemblies.dedup();
+    assemblies
+}
+
+/// The mono runtime, attached to the domain that holds the Celeste assembly.
+pub struct Mono {
+    class_cache: u64,
+    pid: ProcessId,
+    /// The domain the image was found in, unless it was already known from an earlier attach.
+    domain: Option<String>,
+}
+
+impl Mono {
+    /// Searches every assembly of every domain for the one that defines the `Celeste` class, since
+    /// launchers and mods may create domains of their own.
+    pub fn attach(process: &Process, pid: ProcessId) -> Result<Mono, AttachError> {
+        // a previous attach to this process already knows which image it is
+        let known_images: Vec<u64> = RESOLVED.with(pid, |resolved| {
+            resolved.classes.keys().filter(|(_, name)| name == "Celeste").map(|&(class_cache, _)| class_cache).collect()
+        });
+        if let Some(class_cache) = known_images.into_iter().find(|&class_cache| cached_lookup_class(process, pid, class_cache, "Celeste").is_some()) {
+            return Ok(Mono { class_cache, pid, domain: None });
+        }
+
+        let domains = if process.get_module_address("Celeste.bin.x86_64").is_ok() {
+            bundled_domains(process, pid).ok_or(AttachError::DomainListUnreadable)?
+        } else {
+            shared_domains(process)?
+        };
+        if domains.is_empty() {
+            return Err(AttachError::DomainListUnreadable);
+        }
+
+        // newest first: everest loads the game into a domain of its own after the root one
+        for (i, &domain) in domains.iter().enumerate().rev() {
+            for assembly in domain_assemblies(process, domain) {
+                let Ok(image) = process.read::<u64>(assembly + 0x60) else {
+                    continue;
+                };
+                let class_cache = image + 1216;
+                if cached_lookup_class(process, pid, class_cache, "Celeste").is_some() {
+                    let name = domain_name(process, domain);
+                    let name = name.as_ref().and_then(|name| name.validate_utf8().ok()).unwrap_or("?");
+                    let domain = Some(format!("{} (domain {})", name, i + 1));
+                    return Ok(Mono { class_cache, pid, domain });
+                }
+            }
+        }
+        Err(AttachError::AssemblyMissing)
+    }
+}
+
+impl ObjectModel for Mono {
+    fn lookup_class(&self, process: &Process, name: &str) -> Result<u64, AttachError> {
+        cached_lookup_class(process, self.pid, self.class_cache, name).ok_or_else(|| AttachError::ClassNotFound(name.to_owned()))
+    }
+
+    fn field_offset(&self, process: &Process, class: u64, name: &str) -> Result<u64, AttachError> {
+        class_field_offset(process, self.pid, class, name)
+    }
+
+    fn static_storage(&self, process: &Process, class: u64) -> Option<u64> {
+        class_static_fields(process, class)
+    }
+
+    fn instance_class(&self, process: &Process, instance: u64) -> Option<u64> {
+        instance_class(process, instance)
+    }
+
+    fn class_name(&self, process: &Process, class: u64) -> Option<String> {
+        Some(class_name(process, class)?.validate_utf8().ok()?.to_owned())
+    }
+
+    fn layout(&self) -> Layout {
+        managed::MONO
+    }
+
+    fn runtime(&self) -> String {
+        match &self.domain {
+            Some(domain) => format!("mono, {}", domain),
+            None => "mono".to_owned(),
+        }
+    }
+}
+
+/// The images that classes are looked up in after the game's own: the ones defining the
+/// collections and other built-in types that the game keeps its state in.
+const LIBRARY_IMAGES: [&str; 3] = ["mscorlib", "System", "System.Core"];
+
+/// The lookups of a `MonoModule`, which it puts back in `MODULE_CACHES` when dropped.
+#[derive(Default)]
+struct ModuleCache {
+    classes: Vec<(String, mono::Class)>,
+    instance_classes: BTreeMap<u64, u64>,
+    field_offsets: BTreeMap<(u64, String), u64>,
+    name_offset: Option<u64>,
+}
+
+static MODULE_CACHES: ProcessCaches<ModuleCache> = ProcessCaches::new();
+
+/// Mono as seen through asr's unity support, which detects the runtime's version and knows the
+/// structure layouts of each. Its classes don't expose their addresses, so the handles given out
+/// are indices into `classes` instead.
+pub struct MonoModule {
+    pid: ProcessId,
+    module: mono::Module,
+    /// The game's image, followed by whichever of `LIBRARY_IMAGES` are loaded.
+    images: Vec<mono::Image>,
+    classes: RefCell<Vec<(String, mono::Class)>>,
+    /// The handle of each class that objects have been seen with, by its address.
+    instance_classes: RefCell<BTreeMap<u64, u64>>,
+    field_offsets: RefCell<BTreeMap<(u64, String), u64>>,
+    /// Where this version of the runtime keeps a class's name, which is found from the `Celeste`
+    /// class rather than assumed.
+    name_offset: Cell<Option<u64>>,
+}
+
+impl MonoModule {
+    pub fn attach(process: &Process, pid: ProcessId) -> Result<MonoModule, AttachError> {
+        let module = mono::Module::attach_auto_detect(process).ok_or(AttachError::DomainListUnreadable)?;
+        let image = module.get_image(process, "Celeste").ok_or(AttachError::AssemblyMissing)?;
+        let libraries = LIBRARY_IMAGES.iter().filter_map(|name| module.get_image(process, name));
+        let images = std::iter::once(image).chain(libraries).collect();
+        let cache = MODULE_CACHES.take(pid);
+        Ok(MonoModule {
+            pid,
+            module,
+            images,
+            classes: RefCell::new(cache.classes),
+            instance_classes: RefCell::new(cache.instance_classes),
+            field_offsets: RefCell::new(cache.field_offsets),
+            name_offset: Cell::new(cache.name_offset),
+        })
+    }
+
+    fn class(&self, handle: u64) -> Option<mono::Class> {
+        self.classes.borrow().get(handle as usize).map(|&(_, class)| class)
+    }
+
+    /// Finds the name among the first pointers of the class of `Celeste.Instance`. Its namespace
+    /// is also `Celeste`, but every layout puts the name first.
+    fn name_offset(&self, process: &Process) -> Option<u64> {
+        if let Some(offset) = self.name_offset.get() {
+            return Some(offset);
+        }
+        let celeste = self.lookup_class(process, "Celeste").ok()?;
+        let klass = instance_class(process, self.static_object(process, celeste, "Instance").ok()?)?;
+        let offset = (0..0x100).step_by(8).find(|&offset| {
+            let name = process.read::<u64>(klass + offset).and_then(|ptr| process.read::<ArrayCString<128>>(ptr));
+            name.is_ok_and(|name| name.matches("Celeste"))
+        })?;
+        self.name_offset.set(Some(offset));
+        Some(offset)
+    }
+}
+
+impl Drop for MonoModule {
+    fn drop(&mut self) {
+        let cache = ModuleCache {
+            classes: self.classes.take(),
+            instance_classes: self.instance_classes.take(),
+            field_offsets: self.field_offsets.take(),
+            name_offset: self.name_offset.get(),
+        };
+        MODULE_CACHES.keep(self.pid, cache);
+    }
+}
+
+impl ObjectModel for MonoModule {
+    fn lookup_class(&self, process: &Process, name: &str) -> Result<u64, AttachError> {
+        let mut classes = self.classes.borrow_mut();
+        if let Some(index) = classes.iter().position(|(class_name, _)| class_name == name) {
+            return Ok(index as u64);
+        }
+        let class = self
+            .images
+            .iter()
+            .find_map(|image| image.get_class(process, &self.module, name))
+            .ok_or_else(|| AttachError::ClassNotFound(name.to_owned()))?;
+        classes.push((name.to_owned(), class));
+        Ok(classes.len() as u64 - 1)
+    }
+
+    fn field_offset(&self, process: &Process, class: u64, name: &str) -> Result<u64, AttachError> {
+        let key = (class, name.to_owned());
+        if let Some(&offset) = self.field_offsets.borrow().get(&key) {
+            return Ok(offset);
+        }
+        let offset = self
+            .class(class)
+            .and_then(|class| class.get_field_offset(process, &self.module, name))
+            .map(u64::from)
+            .ok_or_else(|| AttachError::FieldNotFound(name.to_owned()))?;
+        self.field_offsets.borrow_mut().insert(key, offset);
+        Ok(offset)
+    }
+
+    fn static_storage(&self, process: &Process, class: u64) -> Option<u64> {
+        Some(self.class(class)?.get_static_table(process, &self.module)?.value())
+    }
+
+    /// Objects can't be mapped to asr's classes directly, so this goes through the class's name.
+    /// Generic instances share the handle of their definition, whose field offsets are the same
+    /// for the reference and 32-bit arguments the game uses.
+    fn instance_class(&self, process: &Process, instance: u64) -> Option<u64> {
+        let klass = instance_class(process, instance)?;
+        if let Some(&handle) = self.instance_classes.borrow().get(&klass) {
+            return Some(handle);
+        }
+        let name = process.read::<ArrayCString<128>>(process.read::<u64>(klass + self.name_offset(process)?).ok()?).ok()?;
+        let handle = self.lookup_class(process, name.validate_utf8().ok()?).ok()?;
+        self.instance_classes.borrow_mut().insert(klass, handle);
+        Some(handle)
+    }
+
+    fn class_name(&self, _process: &Process, class: u64) -> Option<String> {
+        self.classes.borrow().get(class as usize).map(|(name, _)| name.clone())
+    }
+
+    fn layout(&self) -> Layout {
+        managed::MONO
+    }
+
+    fn runtime(&self) -> String {
+        "asr mono".to_owned()
+    }
+}
@@ -0,0 +1,104 @@
+use {
+    crate::{
+        error::AttachError,
+        managed::{self, Layout},
+    },
+    asr::{Process, ProcessId},
+    bytemuck::Pod,
+    static_locks::Mutex,
+};
+
+/// How a managed runtime lays out classes and objects. Classes are identified by an opaque
+/// address, which is whatever the runtime uses to describe a type.
+pub trait ObjectModel {
+    /// Finds a class in the game's assembly by its name, without namespace.
+    fn lookup_class(&self, process: &Process, name: &str) -> Result<u64, AttachError>;
+
+    /// The offset of a field, including inherited ones: from the start of an object for instance
+    /// fields, or from the class's static storage for static ones.
+    fn field_offset(&self, process: &Process, class: u64, name: &str) -> Result<u64, AttachError>;
+
+    /// The address that a class's static field offsets are relative to, if the runtime has one.
+    fn static_storage(&self, process: &Process, class: u64) -> Option<u64>;
+
+    fn instance_class(&self, process: &Process, instance: u64) -> Option<u64>;
+
+    fn class_name(&self, process: &Process, class: u64) -> Option<String>;
+
+    /// Where strings and arrays keep their contents.
+    fn layout(&self) -> Layout;
+
+    /// Which runtime this is, for the log.
+    fn runtime(&self) -> String;
+
+    fn read_string(&self, process: &Process, string: u64) -> Option<String> {
+        managed::read_string(process, &self.layout(), string)
+    }
+
+    /// Reads a static reference field, which is an error when it is null.
+    fn static_object(&self, process: &Process, class: u64, name: &str) -> Result<u64, AttachError> {
+        let object = static_field(self, process, class, name)?;
+        if object == 0 {
+            return Err(AttachError::NullField(name.to_owned()));
+        }
+        Ok(object)
+    }
+}
+
+pub fn field<T: Pod, M: ObjectModel + ?Sized>(model: &M, process: &Process, instance: u64, name: &str) -> Result<T, AttachError> {
+    let unreadable = || AttachError::FieldUnreadable(name.to_owned());
+    let class = model.instance_class(process, instance).ok_or_else(unreadable)?;
+    let offset = model.field_offset(process, class, name)?;
+    process.read(instance + offset).map_err(|_| unreadable())
+}
+
+pub fn static_field<T: Pod, M: ObjectModel + ?Sized>(model: &M, process: &Process, class: u64, name: &str) -> Result<T, AttachError> {
+    let unreadable = || AttachError::FieldUnreadable(name.to_owned());
+    let offset = model.field_offset(process, class, name)?;
+    let storage = model.static_storage(process, class).ok_or_else(unreadable)?;
+    process.read(storage + offset).map_err(|_| unreadable())
+}
+
+/// What an object model resolved in each process, which is handed from one attach to the next
+/// since reattaching happens whenever a sanity check fails.
+pub struct ProcessCaches<T>(Mutex<Vec<(ProcessId, T)>>);
+
+impl<T: Default> ProcessCaches<T> {
+    pub const fn new() -> ProcessCaches<T> {
+        ProcessCaches(Mutex::new(Vec::new()))
+    }
+
+    /// Takes what was kept for a process, or an empty cache the first time it is attached to.
+    pub fn take(&self, pid: ProcessId) -> T {
+        let mut caches = self.0.lock();
+        match caches.iter().position(|(cached_pid, _)| *cached_pid == pid) {
+            Some(index) => caches.swap_remove(index).1,
+            None => T::default(),
+        }
+    }
+
+    /// Works on what is kept for a process in place, for caches shared by every attach to it
+    /// rather than handed from one to the next.
+    pub fn with<R>(&self, pid: ProcessId, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut caches = self.0.lock();
+        let index = match caches.iter().position(|(cached_pid, _)| *cached_pid == pid) {
+            Some(index) => index,
+            None => {
+                caches.push((pid, T::default()));
+                caches.len() - 1
+            }
+        };
+        f(&mut caches[index].1)
+    }
+
+    pub fn keep(&self, pid: ProcessId, cache: T) {
+        let mut caches = self.0.lock();
+        caches.retain(|(cached_pid, _)| *cached_pid != pid);
+        caches.push((pid, cache));
+    }
+
+    /// Drops what was kept for processes other than `pids`, which have exited.
+    pub fn forget_processes(&self, pids: &[ProcessId]) {
+        self.0.lock().retain(|(pid, _)| pids.contains(pid));
+    }
+}
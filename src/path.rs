@@ -0,0 +1,90 @@
+use {
+    crate::object_model::{static_field, ObjectModel},
+    asr::Process,
+    bytemuck::Pod,
+    std::cell::{Cell, RefCell},
+};
+
+/// A chain of fields through the game's objects, written like `SaveData.Instance.TotalDeaths`: a
+/// class, one of its static fields, and then instance fields of whatever each one refers to.
+///
+/// Field offsets are cached per step along with the class they were resolved on, so reading a
+/// path again only costs the pointer chase, unless an object along the way changed class.
+pub struct ObjectPath {
+    class_name: String,
+    root: String,
+    fields: Vec<String>,
+    class: Cell<Option<u64>>,
+    offsets: RefCell<Vec<Option<(u64, u64)>>>,
+}
+
+impl ObjectPath {
+    pub fn new(path: &str) -> ObjectPath {
+        let mut segments = path.split('.').map(str::to_owned);
+        let class_name = segments.next().unwrap_or_default();
+        let root = segments.next().unwrap_or_default();
+        let fields: Vec<String> = segments.collect();
+        ObjectPath {
+            class_name,
+            root,
+            offsets: RefCell::new(vec![None; fields.len()]),
+            fields,
+            class: Cell::new(None),
+        }
+    }
+
+    fn class(&self, model: &dyn ObjectModel, process: &Process) -> Option<u64> {
+        if let Some(class) = self.class.get() {
+            return Some(class);
+        }
+        let class = model.lookup_class(process, &self.class_name).ok()?;
+        self.class.set(Some(class));
+        Some(class)
+    }
+
+    /// The object in the static field, which is reread every time since statics like
+    /// `SaveData.Instance` are reassigned.
+    fn root(&self, model: &dyn ObjectModel, process: &Process) -> Option<u64> {
+        let class = self.class(model, process)?;
+        model.static_object(process, class, &self.root).ok()
+    }
+
+    fn offset(&self, model: &dyn ObjectModel, process: &Process, index: usize, object: u64) -> Option<u64> {
+        let class = model.instance_class(process, object)?;
+        let mut offsets = self.offsets.borrow_mut();
+        if let Some((cached_class, offset)) = offsets[index] {
+            if cached_class == class {
+                return Some(offset);
+            }
+        }
+        let offset = model.field_offset(process, class, &self.fields[index]).ok()?;
+        offsets[index] = Some((class, offset));
+        Some(offset)
+    }
+
+    /// The address of the last field, following every reference before it.
+    fn address(&self, model: &dyn ObjectModel, process: &Process) -> Option<u64> {
+        let last = self.fields.len().checked_sub(1)?;
+        let mut object = self.root(model, process)?;
+        for index in 0..last {
+            let offset = self.offset(model, process, index, object)?;
+            object = process.read::<u64>(object + offset).ok().filter(|&object| object != 0)?;
+        }
+        Some(object + self.offset(model, process, last, object)?)
+    }
+
+    pub fn read<T: Pod>(&self, model: &dyn ObjectModel, process: &Process) -> Option<T> {
+        if self.fields.is_empty() {
+            return static_field(model, process, self.class(model, process)?, &self.root).ok();
+        }
+        process.read(self.address(model, process)?).ok()
+    }
+
+    /// Reads a reference, which is `None` when it is null.
+    pub fn read_object(&self, model: &dyn ObjectModel, process: &Process) -> Option<u64> {
+        if self.fields.is_empty() {
+            return self.root(model, process);
+        }
+        self.read::<u64>(model, process).filter(|&object| object != 0)
+    }
+}
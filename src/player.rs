@@ -0,0 +1,75 @@
+use {
+    crate::{
+        managed::{read_dictionary, read_list},
+        object_model::{field, ObjectModel},
+        path::ObjectPath,
+    },
+    asr::Process,
+    std::cell::Cell,
+};
+
+pub struct Player {
+    pub position: (f32, f32),
+    pub speed: (f32, f32),
+    pub dashes: i32,
+    pub stamina: f32,
+    /// The current state of the player's `StateMachine`, see `PLAYER_STATES`.
+    pub state: i32,
+}
+
+fn vector2(model: &dyn ObjectModel, process: &Process, object: u64, name: &str) -> Option<(f32, f32)> {
+    let [x, y] = field::<[f32; 2], _>(model, process, object, name).ok()?;
+    Some((x, y))
+}
+
+/// Finds the `Player` through the tracker of `Engine.Scene`, which keeps a list of entities for
+/// each tracked type.
+pub struct PlayerReader {
+    entities: ObjectPath,
+    /// The tracker's list of players, which stays the same for as long as the level is loaded, by
+    /// the address of the tracker's entities. A new level has a tracker of its own, while the old
+    /// one's list keeps its player until it is collected.
+    players: Cell<Option<(u64, u64)>>,
+}
+
+impl PlayerReader {
+    pub fn new() -> PlayerReader {
+        PlayerReader {
+            entities: ObjectPath::new("Celeste.Instance.scene.<Tracker>k__BackingField.<Entities>k__BackingField"),
+            players: Cell::new(None),
+        }
+    }
+
+    fn is_player(model: &dyn ObjectModel, process: &Process, entity: u64) -> bool {
+        model.instance_class(process, entity).and_then(|class| model.class_name(process, class)).as_deref() == Some("Player")
+    }
+
+    fn find_player(&self, model: &dyn ObjectModel, process: &Process) -> Option<u64> {
+        let entities = self.entities.read_object(model, process)?;
+        if let Some((_, players)) = self.players.get().filter(|&(tracker_entities, _)| tracker_entities == entities) {
+            let player = read_list::<u64, _>(model, process, players).and_then(|players| players.first().copied());
+            if let Some(player) = player.filter(|&player| PlayerReader::is_player(model, process, player)) {
+                return Some(player);
+            }
+        }
+        // the keys are `Type`s, so the lists are told apart by what they hold instead
+        let (players, player) = read_dictionary::<u64, u64, _>(model, process, entities)?.into_iter().find_map(|(_, list)| {
+            let player = *read_list::<u64, _>(model, process, list)?.first()?;
+            PlayerReader::is_player(model, process, player).then_some((list, player))
+        })?;
+        self.players.set(Some((entities, players)));
+        Some(player)
+    }
+
+    pub fn read(&self, model: &dyn ObjectModel, process: &Process) -> Option<Player> {
+        let player = self.find_player(model, process)?;
+        let state_machine = field::<u64, _>(model, process, player, "StateMachine").ok().filter(|&ptr| ptr != 0)?;
+        Some(Player {
+            position: vector2(model, process, player, "Position")?,
+            speed: vector2(model, process, player, "Speed")?,
+            dashes: field(model, process, player, "Dashes").ok()?,
+            stamina: field(model, process, player, "Stamina").ok()?,
+            state: field(model, process, state_machine, "state").ok()?,
+        })
+    }
+}
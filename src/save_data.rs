@@ -0,0 +1,90 @@
+use {
+    crate::{
+        managed::{read_array, read_list},
+        object_model::{field, ObjectModel},
+        path::ObjectPath,
+    },
+    asr::{time::Duration, Process},
+};
+
+/// One side of a chapter in the save file.
+pub struct ModeStats {
+    pub completed: bool,
+    pub full_clear: bool,
+    pub heart_gem: bool,
+    pub deaths: i32,
+    pub best_time: Duration,
+    pub best_full_clear_time: Duration,
+}
+
+pub struct AreaStats {
+    pub id: i32,
+    pub cassette: bool,
+    /// Indexed by `AreaMode`.
+    pub modes: Vec<ModeStats>,
+}
+
+/// File-wide statistics of the loaded save file.
+pub struct SaveData {
+    /// The address of the object, which changes when another file is loaded.
+    pub address: u64,
+    pub name: String,
+    pub total_deaths: i32,
+    pub total_dashes: i32,
+    pub unlocked_areas: i32,
+    /// `None` until they are read, or if they couldn't be.
+    pub areas: Option<Vec<AreaStats>>,
+}
+
+fn ticks(ticks: i64) -> Duration {
+    Duration::milliseconds(ticks / 10000)
+}
+
+fn read_mode(model: &dyn ObjectModel, process: &Process, mode: u64) -> Option<ModeStats> {
+    Some(ModeStats {
+        completed: field::<u8, _>(model, process, mode, "Completed").ok()? != 0,
+        full_clear: field::<u8, _>(model, process, mode, "FullClear").ok()? != 0,
+        heart_gem: field::<u8, _>(model, process, mode, "HeartGem").ok()? != 0,
+        deaths: field(model, process, mode, "Deaths").ok()?,
+        best_time: ticks(field(model, process, mode, "BestTime").ok()?),
+        best_full_clear_time: ticks(field(model, process, mode, "BestFullClearTime").ok()?),
+    })
+}
+
+fn read_area(model: &dyn ObjectModel, process: &Process, area: u64) -> Option<AreaStats> {
+    let modes = read_array::<u64>(process, &model.layout(), field(model, process, area, "Modes").ok()?)?;
+    Some(AreaStats {
+        id: field(model, process, area, "ID").ok()?,
+        cassette: field::<u8, _>(model, process, area, "Cassette").ok()? != 0,
+        modes: modes.into_iter().map(|mode| read_mode(model, process, mode)).collect::<Option<_>>()?,
+    })
+}
+
+/// Reads `SaveData.Instance`.
+pub struct SaveDataReader {
+    instance: ObjectPath,
+}
+
+impl SaveDataReader {
+    pub fn new() -> SaveDataReader {
+        SaveDataReader { instance: ObjectPath::new("SaveData.Instance") }
+    }
+
+    /// Reads the file-wide values, leaving `areas` unread since they are the bulk of the reads.
+    pub fn read(&self, model: &dyn ObjectModel, process: &Process) -> Option<SaveData> {
+        let address = self.instance.read_object(model, process)?;
+        Some(SaveData {
+            address,
+            name: model.read_string(process, field(model, process, address, "Name").ok()?).unwrap_or_default(),
+            total_deaths: field(model, process, address, "TotalDeaths").ok()?,
+            total_dashes: field(model, process, address, "TotalDashes").ok()?,
+            unlocked_areas: field(model, process, address, "UnlockedAreas").ok()?,
+            areas: None,
+        })
+    }
+
+    pub fn read_areas(&self, model: &dyn ObjectModel, process: &Process, save: &SaveData) -> Option<Vec<AreaStats>> {
+        let areas = read_list::<u64, _>(model, process, field(model, process, save.address, "Areas").ok()?)?;
+        areas.into_iter().map(|area| read_area(model, process, area)).collect()
+    }
+}
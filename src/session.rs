@@ -0,0 +1,128 @@
+use {
+    crate::{
+        managed::{read_hash_set, read_list},
+        object_model::{field, ObjectModel},
+        path::ObjectPath,
+    },
+    asr::Process,
+    std::cell::RefCell,
+};
+
+/// The state of the chapter being played, which is reset whenever it is entered or restarted.
+#[derive(Clone)]
+pub struct Session {
+    /// The address of the object, which changes when the chapter is restarted.
+    pub address: u64,
+    pub deaths: i32,
+    pub dashes: i32,
+    pub grabbed_golden: bool,
+    /// Whether the level it belongs to has been completed.
+    pub completed: bool,
+    /// Where the player comes back after dying.
+    pub respawn_point: Option<(f32, f32)>,
+    /// The checkpoint the chapter was entered from, or `None` for the start of the chapter.
+    pub start_checkpoint: Option<String>,
+    /// The last checkpoint reached, which the chapter would be resumed from.
+    pub checkpoint: Option<String>,
+    /// `None` if the set of flags couldn't be read, rather than it being empty.
+    pub flags: Option<Vec<String>>,
+}
+
+/// `LevelExit.Mode.GoldenBerryRestart`, which the chapter is restarted with after dying with a
+/// golden berry.
+const GOLDEN_BERRY_RESTART: i32 = 3;
+
+/// Reads the `Session` of `Engine.Scene`, if it is a `Level`.
+pub struct SessionReader {
+    scene: ObjectPath,
+    session: ObjectPath,
+    /// The rooms of the loaded map that have a checkpoint, by the address of its `MapData`.
+    checkpoint_rooms: RefCell<Option<(u64, Vec<String>)>>,
+    /// The last checkpoint reached in the session at the given address. The session only knows
+    /// the one it started from, so this is followed from room to room.
+    checkpoint: RefCell<Option<(u64, Option<String>)>>,
+}
+
+impl SessionReader {
+    pub fn new() -> SessionReader {
+        SessionReader {
+            scene: ObjectPath::new("Celeste.Instance.scene"),
+            session: ObjectPath::new("Celeste.Instance.scene.Session"),
+            checkpoint_rooms: RefCell::new(None),
+            checkpoint: RefCell::new(None),
+        }
+    }
+
+    fn is_checkpoint_room(&self, model: &dyn ObjectModel, process: &Process, session: u64, level: &str) -> bool {
+        let Some(map_data) = field::<u64, _>(model, process, session, "MapData").ok().filter(|&ptr| ptr != 0) else {
+            return false;
+        };
+        let mut checkpoint_rooms = self.checkpoint_rooms.borrow_mut();
+        if checkpoint_rooms.as_ref().is_none_or(|(address, _)| *address != map_data) {
+            // a map whose rooms couldn't be read is tried again rather than taken to have none
+            let Some(levels) = field::<u64, _>(model, process, map_data, "Levels").ok().and_then(|levels| read_list::<u64, _>(model, process, levels)) else {
+                return false;
+            };
+            let rooms = levels
+                .into_iter()
+                .filter(|&level| field::<u8, _>(model, process, level, "HasCheckpoint").is_ok_and(|has_checkpoint| has_checkpoint == 1))
+                .filter_map(|level| model.read_string(process, field(model, process, level, "Name").ok()?))
+                .collect();
+            *checkpoint_rooms = Some((map_data, rooms));
+        }
+        checkpoint_rooms.as_ref().is_some_and(|(_, rooms)| rooms.iter().any(|room| room == level))
+    }
+
+    fn checkpoint(&self, model: &dyn ObjectModel, process: &Process, session: u64, level: &str, start_checkpoint: Option<&str>) -> Option<String> {
+        let reached = self.is_checkpoint_room(model, process, session, level);
+        let mut checkpoint = self.checkpoint.borrow_mut();
+        if checkpoint.as_ref().is_none_or(|(address, _)| *address != session) {
+            *checkpoint = Some((session, start_checkpoint.map(str::to_owned)));
+        }
+        let (_, checkpoint) = checkpoint.as_mut()?;
+        if reached {
+            *checkpoint = Some(level.to_owned());
+        }
+        checkpoint.clone()
+    }
+
+    fn scene(&self, model: &dyn ObjectModel, process: &Process) -> Option<(u64, String)> {
+        let scene = self.scene.read_object(model, process)?;
+        Some((scene, model.class_name(process, model.instance_class(process, scene)?)?))
+    }
+
+    /// Whether `Engine.Scene` is the one that restarts the chapter after a death with a golden.
+    pub fn golden_restart(&self, model: &dyn ObjectModel, process: &Process) -> bool {
+        match self.scene(model, process) {
+            Some((scene, name)) if name == "LevelExit" => field::<i32, _>(model, process, scene, "mode").is_ok_and(|mode| mode == GOLDEN_BERRY_RESTART),
+            _ => false,
+        }
+    }
+
+    pub fn read(&self, model: &dyn ObjectModel, process: &Process) -> Option<Session> {
+        let (scene, name) = self.scene(model, process)?;
+        if name != "Level" {
+            return None;
+        }
+        let address = self.session.read_object(model, process)?;
+        // a `Vector2?` is its flag, padded to the alignment of the floats that follow
+        let [has_respawn_point, x, y] = field::<[u32; 3], _>(model, process, address, "RespawnPoint").ok()?;
+        let start_checkpoint = field::<u64, _>(model, process, address, "StartCheckpoint").ok()?;
+        let start_checkpoint = if start_checkpoint == 0 { None } else { model.read_string(process, start_checkpoint) };
+        // the room the player is in
+        let level = model.read_string(process, field(model, process, address, "Level").ok()?).unwrap_or_default();
+        let flags = field::<u64, _>(model, process, address, "Flags").ok()?;
+        Some(Session {
+            address,
+            deaths: field(model, process, address, "Deaths").ok()?,
+            dashes: field(model, process, address, "Dashes").ok()?,
+            grabbed_golden: field::<u8, _>(model, process, address, "GrabbedGolden").ok()? == 1,
+            completed: field::<u8, _>(model, process, scene, "Completed").ok()? == 1,
+            respawn_point: (has_respawn_point & 0xff != 0).then_some((f32::from_bits(x), f32::from_bits(y))),
+            checkpoint: self.checkpoint(model, process, address, &level, start_checkpoint.as_deref()),
+            start_checkpoint,
+            flags: read_hash_set::<u64, _>(model, process, flags)
+                .map(|flags| flags.into_iter().filter_map(|flag| model.read_string(process, flag)).collect()),
+        })
+    }
+}